@@ -0,0 +1,79 @@
+use super::{Error, Result};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+/// Version byte prefixed to every account address before base58check encoding
+pub const ADDRESS_VERSION: u8 = 0;
+
+/// Length of a key type byte followed by a 32 byte public key
+const KEY_LEN: usize = 33;
+
+/// A base58check encoded account address. Parsing validates the checksum,
+/// the version byte and the key length, so a value of this type is always
+/// safe to put on the wire.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Address(String);
+
+impl Address {
+    /// Encodes a key type byte followed by the public key as an address
+    pub fn from_key_bytes(key: &[u8]) -> Result<Address> {
+        if key.len() != KEY_LEN {
+            return Err(Error::InvalidAddress(bs58::encode(key).into_string()));
+        }
+        Ok(Address(
+            bs58::encode(key)
+                .with_check_version(ADDRESS_VERSION)
+                .into_string(),
+        ))
+    }
+
+    /// The key type byte followed by the public key
+    pub fn to_key_bytes(&self) -> Vec<u8> {
+        // the string was validated on construction
+        let mut data = bs58::decode(&self.0)
+            .with_check(Some(ADDRESS_VERSION))
+            .into_vec()
+            .unwrap_or_default();
+        data.drain(..1);
+        data
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for Address {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Address> {
+        let data = bs58::decode(s)
+            .with_check(Some(ADDRESS_VERSION))
+            .into_vec()
+            .map_err(|_| Error::InvalidAddress(s.to_string()))?;
+        // data holds the version byte followed by the key
+        if data.len() != KEY_LEN + 1 {
+            return Err(Error::InvalidAddress(s.to_string()));
+        }
+        Ok(Address(s.to_string()))
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Serialize for Address {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Address {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
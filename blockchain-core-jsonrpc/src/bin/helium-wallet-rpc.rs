@@ -0,0 +1,192 @@
+use blockchain_core_jsonrpc::{Address, Bones, Client, Error, Result, Secret};
+use serde_json::json;
+use std::process;
+use structopt::StructOpt;
+
+/// Exit code for a wallet that is locked or could not be unlocked
+const EXIT_WALLET_LOCKED: i32 = 77;
+/// Exit code for a node that cannot be reached
+const EXIT_UNREACHABLE: i32 = 69;
+/// Exit code for any other error
+const EXIT_FAILURE: i32 = 1;
+
+/// Manage blockchain-node wallets over JSON-RPC.
+///
+/// Exits with 77 when a wallet is locked or the password is wrong, with 69
+/// when the node cannot be reached, and with 1 on any other error.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "helium-wallet-rpc")]
+pub struct Cli {
+    /// URL of the node's JSON-RPC endpoint
+    #[structopt(long, env = "HELIUM_NODE_URL", default_value = "http://localhost:4467")]
+    url: String,
+    /// Print results as JSON
+    #[structopt(long)]
+    json: bool,
+    /// Read the wallet password from this environment variable instead of
+    /// prompting for it
+    #[structopt(long)]
+    password_env: Option<String>,
+    /// Read the wallet password from this file descriptor instead of
+    /// prompting for it
+    #[cfg(unix)]
+    #[structopt(long, parse(try_from_str = parse_password_fd))]
+    password_fd: Option<i32>,
+    #[structopt(subcommand)]
+    cmd: Cmd,
+}
+
+#[derive(Debug, StructOpt)]
+enum Cmd {
+    /// List the wallets held by the node
+    List,
+    /// Create a new wallet, prompting for its password
+    Create,
+    /// Unlock a wallet, prompting for its password
+    Unlock { address: Address },
+    /// Lock a wallet
+    Lock { address: Address },
+    /// Pay HNT from a wallet
+    Pay {
+        address: Address,
+        payee: Address,
+        /// Amount in HNT, with up to 8 decimal places
+        #[structopt(parse(try_from_str = Bones::parse_hnt))]
+        amount: Bones,
+    },
+    /// Print the current block height
+    Height,
+    /// Print the status of a submitted transaction
+    Status { hash: String },
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::from_args();
+    match run(cli).await {
+        Ok(code) => process::exit(code),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(exit_code(&e));
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<i32> {
+    let client = Client::new(cli.url.clone());
+    let (human, json) = match &cli.cmd {
+        Cmd::List => {
+            let wallets = client.list_wallets().await?;
+            let human = wallets
+                .iter()
+                .map(Address::to_string)
+                .collect::<Vec<_>>()
+                .join("\n");
+            (human, json!(wallets))
+        }
+        Cmd::Create => {
+            let password = match read_password(&cli)? {
+                Some(password) => password,
+                None => {
+                    let password = prompt_password("Password: ")?;
+                    if password != prompt_password("Confirm password: ")? {
+                        eprintln!("error: passwords do not match");
+                        return Ok(EXIT_FAILURE);
+                    }
+                    password
+                }
+            };
+            let address = client.create_wallet(password).await?;
+            (address.to_string(), json!({ "address": address }))
+        }
+        Cmd::Unlock { address } => {
+            let password = match read_password(&cli)? {
+                Some(password) => password,
+                None => prompt_password("Password: ")?,
+            };
+            if !client.unlock_wallet(address, &password).await? {
+                eprintln!("error: wrong password for {}", address);
+                return Ok(EXIT_WALLET_LOCKED);
+            }
+            ("unlocked".to_string(), json!({ "unlocked": true }))
+        }
+        Cmd::Lock { address } => {
+            let locked = client.lock_wallet(address).await?;
+            (
+                if locked { "locked" } else { "not locked" }.to_string(),
+                json!({ "locked": locked }),
+            )
+        }
+        Cmd::Pay {
+            address,
+            payee,
+            amount,
+        } => {
+            let hash = client.pay(address, payee, *amount).await?;
+            (hash.clone(), json!({ "hash": hash }))
+        }
+        Cmd::Height => {
+            let height = client.get_height().await?;
+            (height.to_string(), json!({ "height": height }))
+        }
+        Cmd::Status { hash } => {
+            let status = client.pending_transaction_status(hash.clone()).await?;
+            (
+                status.to_string(),
+                json!({ "hash": hash, "status": status }),
+            )
+        }
+    };
+    if cli.json {
+        println!("{}", json);
+    } else {
+        println!("{}", human);
+    }
+    Ok(0)
+}
+
+fn prompt_password(prompt: &str) -> Result<Secret> {
+    Ok(Secret::new(rpassword::prompt_password(prompt)?))
+}
+
+/// The password given through `--password-env` or `--password-fd`, if any
+fn read_password(cli: &Cli) -> Result<Option<Secret>> {
+    if let Some(name) = &cli.password_env {
+        return Secret::from_env(name).map(Some);
+    }
+    #[cfg(unix)]
+    if let Some(fd) = cli.password_fd {
+        use std::os::unix::io::{FromRawFd, OwnedFd};
+        // SAFETY: parse_password_fd checked that the descriptor is open and
+        // not stdio, and nothing else in this process owns it
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+        return Secret::from_fd(fd).map(Some);
+    }
+    Ok(None)
+}
+
+/// Accepts an open descriptor other than stdin, stdout and stderr, since
+/// the password is read until the descriptor is closed
+#[cfg(unix)]
+fn parse_password_fd(s: &str) -> std::result::Result<i32, String> {
+    let fd: i32 = s
+        .parse()
+        .map_err(|_| format!("invalid file descriptor: {}", s))?;
+    if fd <= 2 {
+        return Err(format!("file descriptor {} is stdio", fd));
+    }
+    if std::fs::metadata(format!("/dev/fd/{}", fd)).is_err() {
+        return Err(format!("file descriptor {} is not open", fd));
+    }
+    Ok(fd)
+}
+
+fn exit_code(error: &Error) -> i32 {
+    if error.is_wallet_locked() {
+        EXIT_WALLET_LOCKED
+    } else if error.is_transient() {
+        EXIT_UNREACHABLE
+    } else {
+        EXIT_FAILURE
+    }
+}
@@ -0,0 +1,120 @@
+//! A synchronous client for scripts and tools that do not run an async
+//! runtime. Each call runs the matching `async` method to completion on a
+//! runtime owned by the client, so it must not be used from within an
+//! async context.
+
+use super::{
+    Account, Address, Block, Bones, NodeInfo, NonceStatus, OraclePrice, PaymentEstimate, Peer,
+    PendingStatus, Result, Secret, Transaction,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use tokio::runtime::{Builder, Runtime};
+
+/// Blocking counterpart of `crate::Client`. Clones share the runtime and
+/// the underlying client.
+#[derive(Clone)]
+pub struct Client {
+    client: super::Client,
+    runtime: Arc<Runtime>,
+}
+
+macro_rules! blocking {
+    ($($(#[$attr:meta])* fn $name:ident(&self $(, $arg:ident: $ty:ty)*) -> $ret:ty;)*) => {
+        $(
+            $(#[$attr])*
+            pub fn $name(&self $(, $arg: $ty)*) -> Result<$ret> {
+                self.runtime.block_on(self.client.$name($($arg),*))
+            }
+        )*
+    };
+}
+
+impl Client {
+    pub fn new(address: String) -> Result<Client> {
+        Client::with_client(super::Client::new(address))
+    }
+
+    /// Creates a client that talks to the node over a Unix domain socket
+    #[cfg(unix)]
+    pub fn unix<P: Into<std::path::PathBuf>>(path: P) -> Result<Client> {
+        Client::with_client(super::Client::unix(path))
+    }
+
+    /// Wraps an async client, e.g. one configured with `ClientBuilder`
+    pub fn with_client(client: super::Client) -> Result<Client> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        Ok(Client {
+            client,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// The async client calls are made with
+    pub fn as_async(&self) -> &super::Client {
+        &self.client
+    }
+
+    pub fn post<T: DeserializeOwned, D: Serialize>(&self, data: D) -> Result<T> {
+        self.runtime.block_on(self.client.post(data))
+    }
+
+    pub fn batch<T: DeserializeOwned>(
+        &self,
+        methods: Vec<super::Method>,
+    ) -> Result<Vec<Result<T>>> {
+        self.runtime.block_on(self.client.batch(methods))
+    }
+
+    blocking! {
+        fn get_height(&self) -> usize;
+        fn pending_transaction_status(&self, hash: String) -> PendingStatus;
+        /// See `crate::Client::wait_for_transaction`
+        fn wait_for_transaction(
+            &self,
+            hash: String,
+            timeout: Duration,
+            poll_interval: Duration
+        ) -> Option<u64>;
+        fn create_wallet(&self, password: Secret) -> Address;
+        fn lock_wallet(&self, address: &Address) -> bool;
+        fn list_wallets(&self) -> Vec<Address>;
+        fn unlock_wallet(&self, address: &Address, password: &Secret) -> bool;
+        fn pay(&self, address: &Address, payee: &Address, bones: Bones) -> String;
+        fn pay_multi(&self, address: &Address, payments: Vec<(Address, Bones)>) -> String;
+        /// See `crate::Client::next_nonce`
+        fn next_nonce(&self, address: &Address) -> u64;
+        fn payment_status(&self, address: &Address, nonce: u64) -> NonceStatus;
+        /// See `crate::Client::pay_with_nonce`
+        fn pay_with_nonce(
+            &self,
+            address: &Address,
+            payee: &Address,
+            bones: Bones,
+            nonce: u64
+        ) -> String;
+        fn pay_multi_with_nonce(
+            &self,
+            address: &Address,
+            payments: Vec<(Address, Bones)>,
+            nonce: u64
+        ) -> String;
+        fn estimate_pay(&self, address: &Address, payee: &Address, bones: Bones) -> PaymentEstimate;
+        fn estimate_pay_multi(
+            &self,
+            address: &Address,
+            payments: Vec<(Address, Bones)>
+        ) -> PaymentEstimate;
+        fn get_block_by_height(&self, height: u64) -> Block;
+        fn get_block_by_hash(&self, hash: String) -> Block;
+        fn get_transaction(&self, hash: String) -> Transaction;
+        fn get_account(&self, address: &Address) -> Account;
+        fn get_oracle_price(&self) -> OraclePrice;
+        fn get_oracle_price_at(&self, height: u64) -> OraclePrice;
+        fn get_chain_var(&self, name: String) -> serde_json::Value;
+        fn get_chain_vars(&self) -> HashMap<String, serde_json::Value>;
+        /// Lists the peers known to the node, starting with the node itself
+        fn get_peer_book(&self) -> Vec<Peer>;
+        fn get_node_info(&self) -> NodeInfo;
+    }
+}
@@ -0,0 +1,101 @@
+use super::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Number of bones in one HNT
+pub const BONES_PER_HNT: u64 = 100_000_000;
+
+const HNT_DECIMALS: usize = 8;
+
+/// An amount of HNT counted in bones, the smallest unit of HNT
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize,
+)]
+#[serde(transparent)]
+pub struct Bones(u64);
+
+impl Bones {
+    pub const fn new(bones: u64) -> Bones {
+        Bones(bones)
+    }
+
+    pub const fn get(self) -> u64 {
+        self.0
+    }
+
+    /// Converts a whole number of HNT, failing on overflow
+    pub fn from_hnt(hnt: u64) -> Result<Bones> {
+        hnt.checked_mul(BONES_PER_HNT)
+            .map(Bones)
+            .ok_or_else(|| Error::InvalidAmount(hnt.to_string()))
+    }
+
+    /// Parses a decimal HNT amount such as `"12.5"`. More than 8 decimal
+    /// places cannot be represented in bones and are rejected rather than
+    /// rounded.
+    pub fn parse_hnt(s: &str) -> Result<Bones> {
+        let invalid = || Error::InvalidAmount(s.to_string());
+        let (whole, fraction) = match s.find('.') {
+            Some(index) => (&s[..index], &s[index + 1..]),
+            None => (s, ""),
+        };
+        if (whole.is_empty() && fraction.is_empty())
+            || fraction.len() > HNT_DECIMALS
+            || !whole
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        let whole: u64 = if whole.is_empty() {
+            0
+        } else {
+            whole.parse().map_err(|_| invalid())?
+        };
+        let fraction: u64 = format!("{:0<width$}", fraction, width = HNT_DECIMALS)
+            .parse()
+            .map_err(|_| invalid())?;
+        whole
+            .checked_mul(BONES_PER_HNT)
+            .and_then(|bones| bones.checked_add(fraction))
+            .map(Bones)
+            .ok_or_else(invalid)
+    }
+
+    /// Formats the amount as HNT with all 8 decimal places
+    pub fn to_hnt_string(self) -> String {
+        format!(
+            "{}.{:0width$}",
+            self.0 / BONES_PER_HNT,
+            self.0 % BONES_PER_HNT,
+            width = HNT_DECIMALS
+        )
+    }
+
+    pub fn checked_add(self, other: Bones) -> Option<Bones> {
+        self.0.checked_add(other.0).map(Bones)
+    }
+
+    pub fn checked_sub(self, other: Bones) -> Option<Bones> {
+        self.0.checked_sub(other.0).map(Bones)
+    }
+}
+
+impl From<u64> for Bones {
+    fn from(bones: u64) -> Bones {
+        Bones(bones)
+    }
+}
+
+impl From<Bones> for u64 {
+    fn from(bones: Bones) -> u64 {
+        bones.0
+    }
+}
+
+impl fmt::Display for Bones {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} bones", self.0)
+    }
+}
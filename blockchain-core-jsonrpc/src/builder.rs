@@ -0,0 +1,109 @@
+use super::{Client, HttpTransport, Observer, Transport};
+use reqwest::header::{HeaderName, HeaderValue};
+use std::{sync::Arc, time::Duration};
+
+/// Configures how a `Client` reaches the node. The HTTP options only apply
+/// when no other transport is set.
+pub struct ClientBuilder {
+    http: HttpTransport,
+    transport: Option<Arc<dyn Transport>>,
+    observers: Vec<Arc<dyn Observer>>,
+    timeout: Option<Duration>,
+    retry: Retry,
+}
+
+/// How often and how patiently idempotent requests are retried after a
+/// transport failure
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Retry {
+    pub max_retries: u32,
+    pub backoff: Duration,
+}
+
+impl Default for Retry {
+    fn default() -> Self {
+        Retry {
+            max_retries: 0,
+            backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+impl Retry {
+    /// Delay before the given retry, doubling from the initial backoff
+    pub fn delay(&self, retry: u32) -> Duration {
+        self.backoff * 2u32.saturating_pow(retry.min(16))
+    }
+}
+
+impl ClientBuilder {
+    pub(crate) fn new(address: String) -> ClientBuilder {
+        ClientBuilder {
+            http: HttpTransport::new(address),
+            transport: None,
+            observers: Vec::new(),
+            timeout: None,
+            retry: Retry::default(),
+        }
+    }
+
+    /// Sends requests through `transport` instead of HTTP
+    pub fn transport<T: Transport + 'static>(mut self, transport: T) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Adds an observer that sees every exchange with the node. Observers
+    /// are called in the order they were added.
+    pub fn observer<O: Observer + 'static>(mut self, observer: O) -> Self {
+        self.observers.push(Arc::new(observer));
+        self
+    }
+
+    /// Uses an existing `reqwest::Client`, e.g. one with custom TLS or proxy
+    /// settings, instead of creating a new one
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.http.client = client;
+        self
+    }
+
+    /// Fails any single request that takes longer than `timeout`
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Retries requests that failed in transport up to `max_retries` times,
+    /// waiting `backoff` before the first retry and doubling the wait after
+    /// each one. Only idempotent methods are retried; payments never are.
+    pub fn retries(mut self, max_retries: u32, backoff: Duration) -> Self {
+        self.retry = Retry {
+            max_retries,
+            backoff,
+        };
+        self
+    }
+
+    /// Adds a header to every HTTP request
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.http.headers.insert(name, value);
+        self
+    }
+
+    /// Authenticates every HTTP request with basic auth
+    pub fn basic_auth(mut self, username: String, password: Option<String>) -> Self {
+        self.http.basic_auth = Some((username, password));
+        self
+    }
+
+    pub fn build(self) -> Client {
+        let http = self.http;
+        Client {
+            transport: self.transport.unwrap_or_else(|| Arc::new(http)),
+            observers: self.observers,
+            timeout: self.timeout,
+            retry: self.retry,
+            nonces: Default::default(),
+        }
+    }
+}
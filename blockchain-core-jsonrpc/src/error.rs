@@ -0,0 +1,163 @@
+use super::Address;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use thiserror::Error;
+
+pub type Result<T = ()> = std::result::Result<T, Error>;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    /// The request could not be delivered or the node answered with a
+    /// non-success HTTP status
+    #[error("transport error: {0}")]
+    Http(#[from] reqwest::Error),
+    /// The request could not be delivered over a socket or pipe
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    /// The request could not be serialized
+    #[error("failed to encode request: {0}")]
+    Encode(#[source] serde_json::Error),
+    /// The node answered with a JSON-RPC `error` object
+    #[error("json-rpc error: {0}")]
+    JsonRpc(JsonRpcError),
+    /// The response body could not be decoded into the expected type
+    #[error("failed to decode response: {source}")]
+    Decode {
+        source: serde_json::Error,
+        body: String,
+    },
+    /// The response envelope does not declare JSON-RPC 2.0
+    #[error("unsupported json-rpc version: {0:?}")]
+    InvalidVersion(String),
+    /// The response envelope answers a different request
+    #[error("response id {received} does not match request id {expected:?}")]
+    IdMismatch {
+        expected: String,
+        received: serde_json::Value,
+    },
+    /// The response envelope has neither a `result` nor an `error`
+    #[error("response has neither result nor error")]
+    MissingResult,
+    /// A batch response has no entry for the request with this id
+    #[error("batch response has no entry for request id {0:?}")]
+    MissingResponse(String),
+    /// A string is not a valid base58check account address
+    #[error("invalid address {0:?}")]
+    InvalidAddress(String),
+    /// An amount cannot be represented in bones
+    #[error("invalid amount {0:?}")]
+    InvalidAmount(String),
+    /// The node refused to unlock a wallet with the given password
+    #[error("could not unlock wallet {0}")]
+    UnlockFailed(Address),
+    /// A multi-payee payment lists no payees
+    #[error("payment has no payees")]
+    NoPayees,
+    /// A multi-payee payment lists the same payee more than once
+    #[error("payee {0} is listed more than once")]
+    DuplicatePayee(Address),
+    /// The node rejected a submitted transaction
+    #[error("transaction {hash} failed: {reason}")]
+    TransactionFailed { hash: String, reason: String },
+    /// The node does not know about a submitted transaction
+    #[error("transaction {0} not found")]
+    TransactionNotFound(String),
+    /// A payment was not sent because the payer already used its nonce
+    #[error("nonce {nonce} of {address} is already used")]
+    NonceUsed {
+        address: Address,
+        nonce: u64,
+        hash: Option<String>,
+    },
+    /// A replayed request has no recorded response left
+    #[error("no recorded response for {0}")]
+    NotRecorded(String),
+    /// An environment variable expected to hold a secret is not set
+    #[error("environment variable {0} is not set")]
+    MissingEnv(String),
+    /// An operation did not complete in the time allowed
+    #[error("timed out")]
+    Timeout,
+}
+
+/// The `error` member of a JSON-RPC 2.0 response
+#[derive(Clone, Deserialize, Debug, Serialize, PartialEq)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+impl JsonRpcError {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    pub const INTERNAL_ERROR: i64 = -32603;
+    /// The wallet, transaction or block asked for does not exist
+    pub const NOT_FOUND: i64 = -100;
+    /// The wallet has to be unlocked before it can sign
+    pub const WALLET_LOCKED: i64 = -101;
+
+    pub fn new(code: i64, message: &str) -> JsonRpcError {
+        JsonRpcError {
+            code,
+            message: message.to_string(),
+            data: None,
+        }
+    }
+}
+
+impl fmt::Display for JsonRpcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (code {})", self.message, self.code)?;
+        if let Some(data) = &self.data {
+            write!(f, ": {}", data)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<JsonRpcError> for Error {
+    fn from(error: JsonRpcError) -> Self {
+        Error::JsonRpc(error)
+    }
+}
+
+impl Error {
+    pub(crate) fn decode(source: serde_json::Error, body: &str) -> Self {
+        Error::Decode {
+            source,
+            body: body.to_string(),
+        }
+    }
+
+    /// Failures worth retrying: anything in transport but a client error
+    /// reported by the node or a proxy in front of it
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Error::Http(e) => !matches!(e.status(), Some(status) if status.is_client_error()),
+            Error::Io(_) | Error::Timeout => true,
+            _ => false,
+        }
+    }
+
+    /// Whether the node refused to sign because the wallet is locked, or
+    /// refused to unlock it
+    pub fn is_wallet_locked(&self) -> bool {
+        match self {
+            Error::JsonRpc(e) => e.code == JsonRpcError::WALLET_LOCKED,
+            Error::UnlockFailed(_) => true,
+            _ => false,
+        }
+    }
+
+    /// The raw response body, if this error came from a failed decode
+    pub fn body(&self) -> Option<&str> {
+        match self {
+            Error::Decode { body, .. } => Some(body),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use super::{Address, Bones, Client, Result, BONES_PER_HNT};
+use std::collections::HashSet;
+
+/// Encoded size of a payment transaction without its payees: payer, fee,
+/// nonce and signature fields with their protobuf framing
+const TXN_BASE_SIZE: u64 = 2 + 33 + 1 + 9 + 1 + 9 + 2 + 64;
+/// Encoded size each payee adds: payee and amount fields with framing
+const TXN_PAYEE_SIZE: u64 = 2 + 2 + 33 + 1 + 9;
+
+/// Value of one data credit in USD with 8 decimal places, i.e. $0.00001
+const DC_PRICE: u64 = 1_000;
+
+/// Fee and resulting balance a payment would have, worked out without
+/// submitting it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PaymentEstimate {
+    /// Transaction fee in data credits
+    pub fee_dc: u64,
+    /// HNT burnt to pay the fee when the payer's DC balance cannot cover it
+    pub fee: Bones,
+    /// Amount paid to all payees, excluding the fee
+    pub amount: Bones,
+    /// Payer's HNT balance after the payment clears, or zero if it cannot
+    /// cover the payment
+    pub balance: Bones,
+    /// Reasons the node would reject the payment; empty if it looks valid
+    pub issues: Vec<PaymentIssue>,
+}
+
+impl PaymentEstimate {
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Something that would make the node reject a payment
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PaymentIssue {
+    /// The payment lists no payees
+    NoPayees,
+    /// A payee is listed more than once
+    DuplicatePayee(Address),
+    /// A payee would be paid nothing
+    ZeroAmount(Address),
+    /// The payer's balance does not cover the amount and the fee
+    InsufficientBalance { required: Bones, available: Bones },
+    /// The fee has to be paid in HNT but there is no oracle price to
+    /// convert it with
+    NoOraclePrice,
+    /// The node does not report a chain variable the fee depends on
+    MissingChainVar(String),
+}
+
+impl Client {
+    /// Works out the fee and resulting balance of `pay` without sending it
+    pub async fn estimate_pay(
+        &self,
+        address: &Address,
+        payee: &Address,
+        bones: Bones,
+    ) -> Result<PaymentEstimate> {
+        self.estimate_pay_multi(address, vec![(payee.clone(), bones)])
+            .await
+    }
+
+    /// Works out the fee and resulting balance of `pay_multi` without
+    /// sending it. The fee follows the `txn_fee_multiplier` and
+    /// `dc_payload_size` chain variables and is taken from the payer's DC
+    /// balance when it is large enough, otherwise converted to HNT at the
+    /// current oracle price. The transaction size is approximated, so the
+    /// fee may be off by one payload unit for unusual amounts.
+    pub async fn estimate_pay_multi(
+        &self,
+        address: &Address,
+        payments: Vec<(Address, Bones)>,
+    ) -> Result<PaymentEstimate> {
+        let (account, vars, price) = futures::try_join!(
+            self.get_account(address),
+            self.get_chain_vars(),
+            self.get_oracle_price()
+        )?;
+        let var = |name| vars.get(name).and_then(serde_json::Value::as_u64);
+
+        let mut issues = Vec::new();
+        if payments.is_empty() {
+            issues.push(PaymentIssue::NoPayees);
+        }
+        let mut payees = HashSet::new();
+        for (payee, bones) in &payments {
+            if !payees.insert(payee) {
+                issues.push(PaymentIssue::DuplicatePayee(payee.clone()));
+            }
+            if bones.get() == 0 {
+                issues.push(PaymentIssue::ZeroAmount(payee.clone()));
+            }
+        }
+
+        let size = TXN_BASE_SIZE + TXN_PAYEE_SIZE * payments.len() as u64;
+        let payload_size = var("dc_payload_size").unwrap_or(24).max(1);
+        let multiplier = var("txn_fee_multiplier").unwrap_or_else(|| {
+            issues.push(PaymentIssue::MissingChainVar(
+                "txn_fee_multiplier".to_string(),
+            ));
+            0
+        });
+        let fee_dc = size.div_ceil(payload_size) * multiplier;
+        let fee = if fee_dc <= account.dc_balance {
+            Bones::default()
+        } else if price.price == 0 {
+            issues.push(PaymentIssue::NoOraclePrice);
+            Bones::default()
+        } else {
+            let usd = fee_dc as u128 * DC_PRICE as u128;
+            let bones = (usd * BONES_PER_HNT as u128).div_ceil(price.price as u128);
+            Bones::new(bones as u64)
+        };
+
+        let amount = payments
+            .iter()
+            .map(|(_, bones)| bones.get() as u128)
+            .sum::<u128>();
+        let required = amount + fee.get() as u128;
+        let available = account.balance;
+        let balance = match (available.get() as u128).checked_sub(required) {
+            Some(balance) => Bones::new(balance as u64),
+            None => {
+                issues.push(PaymentIssue::InsufficientBalance {
+                    required: Bones::new(required.min(u64::MAX as u128) as u64),
+                    available,
+                });
+                Bones::default()
+            }
+        };
+        Ok(PaymentEstimate {
+            fee_dc,
+            fee,
+            amount: Bones::new(amount.min(u64::MAX as u128) as u64),
+            balance,
+            issues,
+        })
+    }
+}
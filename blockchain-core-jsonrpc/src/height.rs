@@ -0,0 +1,81 @@
+use super::{Client, Result};
+use futures::stream::{self, Stream};
+use std::time::Duration;
+
+/// A block height seen for the first time by `Client::heights`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NewHeight {
+    pub height: u64,
+    /// The height reported before this one, if any
+    pub previous: Option<u64>,
+}
+
+impl NewHeight {
+    /// Number of blocks that arrived between the two polls without being
+    /// reported on their own
+    pub fn skipped(&self) -> u64 {
+        match self.previous {
+            Some(previous) => self.height.saturating_sub(previous + 1),
+            None => 0,
+        }
+    }
+}
+
+struct Poll {
+    client: Client,
+    last: Option<u64>,
+    wait: Option<Duration>,
+    failures: u32,
+}
+
+impl Client {
+    /// Polls the block height every `poll_interval` and yields each height
+    /// higher than the last one seen, starting with the current one. When
+    /// the node cannot be reached the error is yielded and polling backs
+    /// off, doubling the wait up to `max_backoff` until it answers again.
+    /// The stream never ends on its own.
+    pub fn heights(
+        &self,
+        poll_interval: Duration,
+        max_backoff: Duration,
+    ) -> impl Stream<Item = Result<NewHeight>> + Send + 'static {
+        let poll = Poll {
+            client: self.clone(),
+            last: None,
+            wait: None,
+            failures: 0,
+        };
+        stream::unfold(poll, move |mut poll| async move {
+            loop {
+                if let Some(wait) = poll.wait.take() {
+                    tokio::time::sleep(wait).await;
+                }
+                match poll.client.get_height().await {
+                    Ok(height) => {
+                        let height = height as u64;
+                        poll.failures = 0;
+                        poll.wait = Some(poll_interval);
+                        if !matches!(poll.last, Some(last) if height <= last) {
+                            let new = NewHeight {
+                                height,
+                                previous: poll.last.replace(height),
+                            };
+                            return Some((Ok(new), poll));
+                        }
+                    }
+                    Err(e) => {
+                        poll.wait = Some(if e.is_transient() {
+                            poll.failures += 1;
+                            let backoff =
+                                poll_interval * 2u32.saturating_pow(poll.failures.min(16));
+                            backoff.min(max_backoff.max(poll_interval))
+                        } else {
+                            poll_interval
+                        });
+                        return Some((Err(e), poll));
+                    }
+                }
+            }
+        })
+    }
+}
@@ -1,18 +1,65 @@
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use std::result::Result;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
+mod address;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+mod bones;
+mod builder;
+mod error;
+mod fee;
+mod height;
+#[cfg(feature = "mock")]
+pub mod mock;
+mod nonce;
+mod observer;
+mod pool;
+mod secret;
+mod transport;
 mod types;
+mod wallet;
+pub use address::{Address, ADDRESS_VERSION};
+pub use bones::{Bones, BONES_PER_HNT};
+pub use builder::ClientBuilder;
+use builder::Retry;
+pub use error::{Error, JsonRpcError, Result};
+pub use fee::{PaymentEstimate, PaymentIssue};
+pub use height::NewHeight;
+pub use nonce::NonceStatus;
+use nonce::Nonces;
+pub use observer::{redact, LogObserver, Observer, RecordObserver, REDACTED};
+pub use pool::ClientPool;
+pub use secret::Secret;
+#[cfg(unix)]
+pub use transport::UnixTransport;
+pub use transport::{HttpTransport, ReplayTransport, StdioTransport, Transport};
 use types::*;
+pub use types::{
+    Account, AccountParams, Block, BlockParams, BlockTransaction, ChainVarParams, LockParams,
+    MultiPaymentParams, NodeInfo, OraclePrice, OraclePriceParams, Password, Payment, PaymentParams,
+    Peer, PeerBookParams, PendingStatus, PendingTxnStatus, Transaction, TransactionParams,
+    UnlockParams,
+};
+pub use wallet::UnlockedWallet;
 
+#[derive(Clone)]
 pub struct Client {
-    address: String,
-    client: reqwest::Client,
+    transport: Arc<dyn Transport>,
+    observers: Vec<Arc<dyn Observer>>,
+    timeout: Option<Duration>,
+    retry: Retry,
+    nonces: Arc<Mutex<Nonces>>,
 }
 
+/// A request to the node, used directly when building a batch
 #[derive(Clone, Deserialize, Debug, Serialize)]
 #[serde(tag = "method")]
 #[serde(rename_all = "snake_case")]
-enum Method {
+pub enum Method {
     WalletList,
     BlockHeight,
     WalletCreate { params: Password },
@@ -20,130 +67,346 @@ enum Method {
     WalletLock { params: LockParams },
     PendingTransactionStatus { params: PendingTxnStatus },
     WalletPay { params: PaymentParams },
+    WalletPayMulti { params: MultiPaymentParams },
+    BlockGet { params: BlockParams },
+    TransactionGet { params: TransactionParams },
+    AccountGet { params: AccountParams },
+    OraclePriceCurrent,
+    OraclePriceGet { params: OraclePriceParams },
+    ChainVarGet { params: ChainVarParams },
+    ChainVarsGet,
+    PeerBook { params: PeerBookParams },
+    InfoSummary,
+}
+
+impl Method {
+    /// Whether sending the method twice has the same effect as sending it
+    /// once, which makes it safe to retry
+    pub fn is_idempotent(&self) -> bool {
+        match self {
+            Method::WalletCreate { .. }
+            | Method::WalletPay { .. }
+            | Method::WalletPayMulti { .. } => false,
+            Method::WalletList
+            | Method::BlockHeight
+            | Method::WalletUnlock { .. }
+            | Method::WalletLock { .. }
+            | Method::PendingTransactionStatus { .. }
+            | Method::BlockGet { .. }
+            | Method::TransactionGet { .. }
+            | Method::AccountGet { .. }
+            | Method::OraclePriceCurrent
+            | Method::OraclePriceGet { .. }
+            | Method::ChainVarGet { .. }
+            | Method::ChainVarsGet
+            | Method::PeerBook { .. }
+            | Method::InfoSummary => true,
+        }
+    }
 }
 
 impl Client {
     pub fn new(address: String) -> Client {
-        Client {
-            address,
-            client: reqwest::Client::new(),
+        Client::builder(address).build()
+    }
+
+    pub fn builder(address: String) -> ClientBuilder {
+        ClientBuilder::new(address)
+    }
+
+    /// Creates a client that talks to the node over a Unix domain socket
+    #[cfg(unix)]
+    pub fn unix<P: Into<std::path::PathBuf>>(path: P) -> Client {
+        Client::with_transport(UnixTransport::new(path))
+    }
+
+    /// Creates a client with default settings on top of any transport
+    pub fn with_transport<T: Transport + 'static>(transport: T) -> Client {
+        ClientBuilder::new(String::new())
+            .transport(transport)
+            .build()
+    }
+
+    /// Posts arbitrary data to the node and decodes the whole response body,
+    /// returning any JSON-RPC `error` object as `Error::JsonRpc`
+    pub async fn post<T: DeserializeOwned, D: Serialize>(&self, data: D) -> Result<T> {
+        let body = self.send(&data, false).await?;
+        if let Ok(response) = serde_json::from_str::<ErrorResponse>(&body) {
+            return Err(response.error.into());
         }
+        serde_json::from_str(&body).map_err(|e| Error::decode(e, &body))
     }
 
-    pub async fn post<T: DeserializeOwned, D: Serialize>(
-        &self,
-        data: D,
-    ) -> Result<T, Box<dyn std::error::Error>> {
-        let request = self.client.post(&self.address).json(&data);
-        let body = request.send().await?.text().await?;
-        let result: T = serde_json::from_str(&body)?;
-        Ok(result)
-    }
-
-    pub async fn get_height(&self) -> Result<usize, Box<dyn std::error::Error>> {
-        #[derive(Clone, Deserialize, Debug)]
-        struct Response {
-            result: usize,
+    /// Sends the data, retrying transport failures when `idempotent`
+    async fn send<D: Serialize>(&self, data: &D, idempotent: bool) -> Result<String> {
+        let request = serde_json::to_value(data).map_err(Error::Encode)?;
+        let mut retry = 0;
+        loop {
+            match self.send_once(&request).await {
+                Err(e) if idempotent && retry < self.retry.max_retries && e.is_transient() => {
+                    tokio::time::sleep(self.retry.delay(retry)).await;
+                    retry += 1;
+                }
+                result => return result,
+            }
         }
+    }
 
-        let request = JsonRpc::new(Method::BlockHeight);
-        let result: Response = self.post(request).await?;
-        Ok(result.result)
+    async fn send_once(&self, request: &serde_json::Value) -> Result<String> {
+        for observer in &self.observers {
+            observer.on_request(request);
+        }
+        let start = Instant::now();
+        let send = self.transport.send(request.to_string());
+        let response = match self.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, send)
+                .await
+                .unwrap_or(Err(Error::Timeout)),
+            None => send.await,
+        };
+        let elapsed = start.elapsed();
+        for observer in &self.observers {
+            observer.on_response(request, response.as_deref(), elapsed);
+        }
+        response
     }
 
-    pub async fn pending_transaction_status(
-        &self,
-        hash: String,
-    ) -> Result<String, Box<dyn std::error::Error>> {
-        #[derive(Clone, Deserialize, Debug)]
-        struct Response {
-            result: String,
+    async fn call<T: DeserializeOwned>(&self, method: Method) -> Result<T> {
+        let request = JsonRpc::new(method);
+        let body = self
+            .send(&request, request.method().is_idempotent())
+            .await?;
+        let response: Response<T> =
+            serde_json::from_str(&body).map_err(|e| Error::decode(e, &body))?;
+        response.into_result(request.id())
+    }
+
+    /// Sends all methods in a single JSON-RPC batch. Results are returned in
+    /// the order of `methods`, each one failing or succeeding on its own;
+    /// use `serde_json::Value` for `T` when mixing methods.
+    pub async fn batch<T: DeserializeOwned>(&self, methods: Vec<Method>) -> Result<Vec<Result<T>>> {
+        if methods.is_empty() {
+            return Ok(Vec::new());
+        }
+        let idempotent = methods.iter().all(Method::is_idempotent);
+        let requests: Vec<JsonRpc> = methods.into_iter().map(JsonRpc::new).collect();
+        let body = self.send(&requests, idempotent).await?;
+        // a batch the node cannot process at all is answered with a single error
+        if let Ok(response) = serde_json::from_str::<ErrorResponse>(&body) {
+            return Err(response.error.into());
         }
+        let responses: Vec<Response<serde_json::Value>> =
+            serde_json::from_str(&body).map_err(|e| Error::decode(e, &body))?;
+        let mut responses: HashMap<String, Response<serde_json::Value>> = responses
+            .into_iter()
+            .filter_map(|response| {
+                let id = response.id.as_str()?.to_string();
+                Some((id, response))
+            })
+            .collect();
+
+        Ok(requests
+            .iter()
+            .map(|request| {
+                let response = responses
+                    .remove(request.id())
+                    .ok_or_else(|| Error::MissingResponse(request.id().to_string()))?;
+                let result = response.into_result(request.id())?;
+                serde_json::from_value(result.clone())
+                    .map_err(|e| Error::decode(e, &result.to_string()))
+            })
+            .collect())
+    }
+
+    pub async fn get_height(&self) -> Result<usize> {
+        self.call(Method::BlockHeight).await
+    }
 
-        let request = JsonRpc::new(Method::PendingTransactionStatus {
+    pub async fn pending_transaction_status(&self, hash: String) -> Result<PendingStatus> {
+        self.call(Method::PendingTransactionStatus {
             params: PendingTxnStatus { hash },
-        });
-        let result: Response = self.post(request).await?;
-        Ok(result.result)
+        })
+        .await
     }
 
-    pub async fn create_wallet(
+    /// Polls the status of a submitted transaction every `poll_interval`
+    /// until it clears, then until the node reports the height of the block
+    /// that includes it. A failed or unknown transaction is returned as an
+    /// error, as is one still pending after `timeout`. The height is `None`
+    /// when the node cannot look transactions up, or has not indexed this
+    /// one by the time `timeout` runs out.
+    pub async fn wait_for_transaction(
         &self,
-        password: String,
-    ) -> Result<String, Box<dyn std::error::Error>> {
-        let request = JsonRpc::new(Method::WalletCreate {
-            params: Password { password },
-        });
-        let result: Response = self.post(request).await?;
-        Ok(result.result)
+        hash: String,
+        timeout: Duration,
+        poll_interval: Duration,
+    ) -> Result<Option<u64>> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        let cleared = async {
+            loop {
+                match self.pending_transaction_status(hash.clone()).await? {
+                    PendingStatus::Pending => tokio::time::sleep(poll_interval).await,
+                    PendingStatus::Cleared => return Ok(()),
+                    PendingStatus::Failed(reason) => {
+                        return Err(Error::TransactionFailed {
+                            hash: hash.clone(),
+                            reason,
+                        })
+                    }
+                    PendingStatus::NotFound => {
+                        return Err(Error::TransactionNotFound(hash.clone()))
+                    }
+                }
+            }
+        };
+        tokio::time::timeout_at(deadline, cleared)
+            .await
+            .map_err(|_| Error::Timeout)??;
+
+        let indexed = async {
+            loop {
+                match self.get_transaction(hash.clone()).await {
+                    Ok(Transaction {
+                        height: Some(height),
+                        ..
+                    }) => return Ok(Some(height)),
+                    Err(Error::JsonRpc(e)) if e.code == JsonRpcError::METHOD_NOT_FOUND => {
+                        return Ok(None)
+                    }
+                    Ok(_) => (),
+                    Err(Error::JsonRpc(e)) if e.code == JsonRpcError::NOT_FOUND => (),
+                    Err(e) => return Err(e),
+                }
+                tokio::time::sleep(poll_interval).await;
+            }
+        };
+        // the transaction cleared, so running out of time only leaves its
+        // height unknown
+        tokio::time::timeout_at(deadline, indexed)
+            .await
+            .unwrap_or(Ok(None))
     }
 
-    pub async fn lock_wallet(&self, address: String) -> Result<bool, Box<dyn std::error::Error>> {
-        #[derive(Clone, Deserialize, Debug)]
-        struct Response {
-            result: bool,
-        }
+    pub async fn create_wallet(&self, password: Secret) -> Result<Address> {
+        self.call(Method::WalletCreate {
+            params: Password { password },
+        })
+        .await
+    }
 
-        let request = JsonRpc::new(Method::WalletLock {
-            params: LockParams { address },
-        });
-        let result: Response = self.post(request).await?;
-        Ok(result.result)
+    pub async fn lock_wallet(&self, address: &Address) -> Result<bool> {
+        self.call(Method::WalletLock {
+            params: LockParams {
+                address: address.clone(),
+            },
+        })
+        .await
     }
-    pub async fn list_wallets(&self) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-        #[derive(Clone, Deserialize, Debug)]
-        struct Response {
-            result: Vec<String>,
-        }
 
-        let request = JsonRpc::new(Method::WalletList);
-        let result: Response = self.post(request).await?;
-        Ok(result.result)
+    pub async fn list_wallets(&self) -> Result<Vec<Address>> {
+        self.call(Method::WalletList).await
     }
 
-    pub async fn unlock_wallet(
-        &self,
-        address: &str,
-        password: &str,
-    ) -> Result<bool, Box<dyn std::error::Error>> {
-        let request = JsonRpc::new(Method::WalletUnlock {
+    pub async fn unlock_wallet(&self, address: &Address, password: &Secret) -> Result<bool> {
+        self.call(Method::WalletUnlock {
             params: UnlockParams {
-                password: password.into(),
-                address: address.into(),
+                password: password.clone(),
+                address: address.clone(),
             },
-        });
+        })
+        .await
+    }
 
-        #[derive(Deserialize)]
-        struct Response {
-            result: bool,
-        }
-        let result: Response = self.post(request).await?;
-        Ok(result.result)
+    pub async fn pay(&self, address: &Address, payee: &Address, bones: Bones) -> Result<String> {
+        let result: PaymentResult = self
+            .call(Method::WalletPay {
+                params: PaymentParams {
+                    address: address.clone(),
+                    payee: payee.clone(),
+                    bones,
+                    nonce: None,
+                },
+            })
+            .await?;
+        Ok(result.hash)
     }
 
-    pub async fn pay(
+    /// Pays several payees in a single transaction. The list must be
+    /// non-empty and name each payee once; this is checked before anything
+    /// is sent.
+    pub async fn pay_multi(
         &self,
-        address: &str,
-        payee: &str,
-        bones: usize,
-    ) -> Result<String, Box<dyn std::error::Error>> {
-        #[derive(Clone, Deserialize, Debug)]
-        struct Response {
-            result: Result,
-        }
-        #[derive(Clone, Deserialize, Debug)]
-        struct Result {
-            hash: String,
-        }
+        address: &Address,
+        payments: Vec<(Address, Bones)>,
+    ) -> Result<String> {
+        let params = MultiPaymentParams::new(address.clone(), payments)?;
+        let result: PaymentResult = self.call(Method::WalletPayMulti { params }).await?;
+        Ok(result.hash)
+    }
+
+    pub async fn get_block_by_height(&self, height: u64) -> Result<Block> {
+        self.call(Method::BlockGet {
+            params: BlockParams::Height { height },
+        })
+        .await
+    }
 
-        let request = JsonRpc::new(Method::WalletPay {
-            params: PaymentParams {
-                address: address.to_string(),
-                payee: payee.to_string(),
-                bones,
+    pub async fn get_block_by_hash(&self, hash: String) -> Result<Block> {
+        self.call(Method::BlockGet {
+            params: BlockParams::Hash { hash },
+        })
+        .await
+    }
+
+    pub async fn get_transaction(&self, hash: String) -> Result<Transaction> {
+        self.call(Method::TransactionGet {
+            params: TransactionParams { hash },
+        })
+        .await
+    }
+
+    pub async fn get_account(&self, address: &Address) -> Result<Account> {
+        self.call(Method::AccountGet {
+            params: AccountParams {
+                address: address.clone(),
+            },
+        })
+        .await
+    }
+
+    pub async fn get_oracle_price(&self) -> Result<OraclePrice> {
+        self.call(Method::OraclePriceCurrent).await
+    }
+
+    pub async fn get_oracle_price_at(&self, height: u64) -> Result<OraclePrice> {
+        self.call(Method::OraclePriceGet {
+            params: OraclePriceParams { height },
+        })
+        .await
+    }
+
+    pub async fn get_chain_var(&self, name: String) -> Result<serde_json::Value> {
+        self.call(Method::ChainVarGet {
+            params: ChainVarParams { name },
+        })
+        .await
+    }
+
+    pub async fn get_chain_vars(&self) -> Result<HashMap<String, serde_json::Value>> {
+        self.call(Method::ChainVarsGet).await
+    }
+
+    /// Lists the peers known to the node, starting with the node itself
+    pub async fn get_peer_book(&self) -> Result<Vec<Peer>> {
+        self.call(Method::PeerBook {
+            params: PeerBookParams {
+                addr: "self".to_string(),
             },
-        });
-        let result: Response = self.post(request).await?;
-        Ok(result.result.hash)
+        })
+        .await
+    }
+
+    pub async fn get_node_info(&self) -> Result<NodeInfo> {
+        self.call(Method::InfoSummary).await
     }
 }
@@ -0,0 +1,521 @@
+//! An in-process stand-in for a blockchain-node, for testing code built on
+//! `Client` without a real node. The mock keeps wallets and a ledger in
+//! memory, clears pending transactions after a configurable number of
+//! simulated blocks and can be told to fail requests.
+
+use super::{
+    types::JSONRPC_VERSION, Account, Address, Block, BlockParams, BlockTransaction, Bones, Client,
+    Error, JsonRpcError, Method, MultiPaymentParams, NodeInfo, OraclePrice, Peer, PendingStatus,
+    Result, Secret, Transaction, Transport,
+};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    io,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+/// A mock node, used as the transport of a `Client`. Clones share state, so
+/// a test can keep one handle to drive the node while a client uses another.
+#[derive(Clone)]
+pub struct MockNode {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+struct State {
+    height: u64,
+    clear_after: u64,
+    oracle_price: u64,
+    chain_vars: HashMap<String, Value>,
+    wallets: BTreeMap<Address, Wallet>,
+    accounts: HashMap<Address, Ledger>,
+    pending: BTreeMap<String, Pending>,
+    transactions: HashMap<String, Transaction>,
+    statuses: HashMap<String, PendingStatus>,
+    blocks: BTreeMap<u64, Vec<String>>,
+    next_hash: u64,
+    errors: HashMap<String, VecDeque<JsonRpcError>>,
+    transport_errors: usize,
+    requests: Vec<String>,
+}
+
+struct Wallet {
+    password: Secret,
+    unlocked: bool,
+}
+
+#[derive(Default)]
+struct Ledger {
+    balance: u64,
+    nonce: u64,
+    speculative_nonce: u64,
+    dc_balance: u64,
+}
+
+struct Pending {
+    payer: Address,
+    payments: Vec<(Address, Bones)>,
+    nonce: u64,
+    submitted: u64,
+}
+
+impl Default for MockNode {
+    fn default() -> MockNode {
+        MockNode::new()
+    }
+}
+
+impl MockNode {
+    /// A node at height 1 that clears transactions in the next block
+    pub fn new() -> MockNode {
+        let mut state = State {
+            height: 1,
+            clear_after: 1,
+            oracle_price: 100_000_000,
+            ..Default::default()
+        };
+        state
+            .chain_vars
+            .insert("txn_fee_multiplier".to_string(), json!(5000));
+        state
+            .chain_vars
+            .insert("dc_payload_size".to_string(), json!(24));
+        MockNode {
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    /// A client with default settings talking to this node
+    pub fn client(&self) -> Client {
+        Client::with_transport(self.clone())
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Creates a locked wallet holding `balance`
+    pub fn add_wallet(&self, password: &str, balance: Bones) -> Address {
+        let mut state = self.state();
+        let address = state.create_wallet(Secret::from(password));
+        state.ledger(&address).balance = balance.get();
+        address
+    }
+
+    /// Creates an account without a wallet on the node, e.g. a payee
+    pub fn add_account(&self, balance: Bones) -> Address {
+        let address = random_address();
+        self.state().ledger(&address).balance = balance.get();
+        address
+    }
+
+    pub fn set_balance(&self, address: &Address, balance: Bones) {
+        self.state().ledger(address).balance = balance.get();
+    }
+
+    pub fn balance(&self, address: &Address) -> Bones {
+        Bones::new(self.state().ledger(address).balance)
+    }
+
+    pub fn set_dc_balance(&self, address: &Address, dc: u64) {
+        self.state().ledger(address).dc_balance = dc;
+    }
+
+    pub fn is_unlocked(&self, address: &Address) -> bool {
+        matches!(self.state().wallets.get(address), Some(wallet) if wallet.unlocked)
+    }
+
+    pub fn height(&self) -> u64 {
+        self.state().height
+    }
+
+    /// Number of blocks after submission that a transaction clears in
+    pub fn set_clear_after(&self, blocks: u64) {
+        self.state().clear_after = blocks;
+    }
+
+    /// Adds `blocks` blocks, clearing every transaction that is due
+    pub fn advance_blocks(&self, blocks: u64) {
+        let mut state = self.state();
+        for _ in 0..blocks {
+            state.height += 1;
+            state.clear_pending();
+        }
+    }
+
+    /// Marks a pending transaction as failed with `reason`
+    pub fn fail_transaction(&self, hash: &str, reason: &str) {
+        let mut state = self.state();
+        if state.pending.remove(hash).is_some() {
+            state
+                .statuses
+                .insert(hash.to_string(), PendingStatus::Failed(reason.to_string()));
+        }
+    }
+
+    /// HNT price in USD with 8 decimal places
+    pub fn set_oracle_price(&self, price: u64) {
+        self.state().oracle_price = price;
+    }
+
+    pub fn set_chain_var(&self, name: &str, value: Value) {
+        self.state().chain_vars.insert(name.to_string(), value);
+    }
+
+    /// Answers the next request for `method`, e.g. `"wallet_pay"`, with
+    /// `error` instead of handling it. Errors queue up when set repeatedly.
+    pub fn fail_next(&self, method: &str, error: JsonRpcError) {
+        self.state()
+            .errors
+            .entry(method.to_string())
+            .or_default()
+            .push_back(error);
+    }
+
+    /// Fails the next `count` requests in transport, before they reach the
+    /// node, as if the connection had been refused
+    pub fn fail_transport(&self, count: usize) {
+        self.state().transport_errors = count;
+    }
+
+    /// Names of the methods received so far, in order, including requests
+    /// that failed
+    pub fn requests(&self) -> Vec<String> {
+        self.state().requests.clone()
+    }
+
+    fn handle(&self, request: Value) -> Value {
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let name = request
+            .get("method")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let mut state = self.state();
+        state.requests.push(name.clone());
+        let result = match state.errors.get_mut(&name).and_then(VecDeque::pop_front) {
+            Some(error) => Err(error),
+            None => match serde_json::from_value::<Method>(request) {
+                Ok(method) => state.dispatch(method),
+                Err(e) => Err(JsonRpcError::new(
+                    JsonRpcError::INVALID_PARAMS,
+                    &e.to_string(),
+                )),
+            },
+        };
+        match result {
+            Ok(result) => json!({"jsonrpc": JSONRPC_VERSION, "id": id, "result": result}),
+            Err(error) => json!({"jsonrpc": JSONRPC_VERSION, "id": id, "error": error}),
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for MockNode {
+    async fn send(&self, request: String) -> Result<String> {
+        {
+            let mut state = self.state();
+            if state.transport_errors > 0 {
+                state.transport_errors -= 1;
+                return Err(io::Error::from(io::ErrorKind::ConnectionRefused).into());
+            }
+        }
+        let request: Value = serde_json::from_str(&request).map_err(Error::Encode)?;
+        let response = match request {
+            Value::Array(requests) => {
+                Value::Array(requests.into_iter().map(|r| self.handle(r)).collect())
+            }
+            request => self.handle(request),
+        };
+        Ok(response.to_string())
+    }
+}
+
+type MethodResult = std::result::Result<Value, JsonRpcError>;
+
+fn not_found(what: &str) -> JsonRpcError {
+    JsonRpcError::new(JsonRpcError::NOT_FOUND, &format!("{} not found", what))
+}
+
+fn invalid_params(message: &str) -> JsonRpcError {
+    JsonRpcError::new(JsonRpcError::INVALID_PARAMS, message)
+}
+
+fn to_value<T: serde::Serialize>(value: T) -> MethodResult {
+    serde_json::to_value(value)
+        .map_err(|e| JsonRpcError::new(JsonRpcError::INTERNAL_ERROR, &e.to_string()))
+}
+
+fn random_address() -> Address {
+    // an ed25519 key type byte followed by a random public key
+    let mut key = [0u8; 33];
+    key[0] = 1;
+    key[1..].copy_from_slice(&rand::random::<[u8; 32]>());
+    Address::from_key_bytes(&key).expect("valid key length")
+}
+
+fn block_hash(height: u64) -> String {
+    format!("mock-block-{}", height)
+}
+
+impl State {
+    fn ledger(&mut self, address: &Address) -> &mut Ledger {
+        self.accounts.entry(address.clone()).or_default()
+    }
+
+    fn create_wallet(&mut self, password: Secret) -> Address {
+        let address = random_address();
+        self.wallets.insert(
+            address.clone(),
+            Wallet {
+                password,
+                unlocked: false,
+            },
+        );
+        self.ledger(&address);
+        address
+    }
+
+    fn unlocked_wallet(&self, address: &Address) -> std::result::Result<(), JsonRpcError> {
+        match self.wallets.get(address) {
+            None => Err(not_found("wallet")),
+            Some(wallet) if !wallet.unlocked => Err(JsonRpcError::new(
+                JsonRpcError::WALLET_LOCKED,
+                "wallet is locked",
+            )),
+            Some(_) => Ok(()),
+        }
+    }
+
+    fn dispatch(&mut self, method: Method) -> MethodResult {
+        match method {
+            Method::WalletList => to_value(self.wallets.keys().collect::<Vec<_>>()),
+            Method::BlockHeight => to_value(self.height),
+            Method::WalletCreate { params } => to_value(self.create_wallet(params.password)),
+            Method::WalletUnlock { params } => {
+                let wallet = self
+                    .wallets
+                    .get_mut(&params.address)
+                    .ok_or_else(|| not_found("wallet"))?;
+                wallet.unlocked = wallet.password == params.password;
+                to_value(wallet.unlocked)
+            }
+            Method::WalletLock { params } => {
+                let wallet = self
+                    .wallets
+                    .get_mut(&params.address)
+                    .ok_or_else(|| not_found("wallet"))?;
+                wallet.unlocked = false;
+                to_value(true)
+            }
+            Method::PendingTransactionStatus { params } => {
+                let status = if self.pending.contains_key(&params.hash) {
+                    PendingStatus::Pending
+                } else {
+                    self.statuses
+                        .get(&params.hash)
+                        .cloned()
+                        .unwrap_or(PendingStatus::NotFound)
+                };
+                to_value(status)
+            }
+            Method::WalletPay { params } => self.submit(
+                params.address,
+                vec![(params.payee, params.bones)],
+                params.nonce,
+            ),
+            Method::WalletPayMulti { params } => {
+                let payments = params
+                    .payments
+                    .into_iter()
+                    .map(|payment| (payment.payee, payment.bones))
+                    .collect();
+                // apply the same checks a real node would
+                let nonce = params.nonce;
+                let params = MultiPaymentParams::new(params.address, payments)
+                    .map_err(|e| invalid_params(&e.to_string()))?;
+                let payments = params
+                    .payments
+                    .into_iter()
+                    .map(|payment| (payment.payee, payment.bones))
+                    .collect();
+                self.submit(params.address, payments, nonce)
+            }
+            Method::BlockGet { params } => {
+                let height = match params {
+                    BlockParams::Height { height } => height,
+                    BlockParams::Hash { hash } => hash
+                        .strip_prefix("mock-block-")
+                        .and_then(|height| height.parse().ok())
+                        .ok_or_else(|| not_found("block"))?,
+                };
+                if height == 0 || height > self.height {
+                    return Err(not_found("block"));
+                }
+                let transactions = self
+                    .blocks
+                    .get(&height)
+                    .into_iter()
+                    .flatten()
+                    .map(|hash| BlockTransaction {
+                        hash: hash.clone(),
+                        kind: "payment_v2".to_string(),
+                    })
+                    .collect();
+                to_value(Block {
+                    hash: block_hash(height),
+                    height,
+                    prev_hash: block_hash(height - 1),
+                    time: 1_600_000_000 + height * 60,
+                    transactions,
+                })
+            }
+            Method::TransactionGet { params } => self
+                .transactions
+                .get(&params.hash)
+                .ok_or_else(|| not_found("transaction"))
+                .and_then(to_value),
+            Method::AccountGet { params } => {
+                let ledger = self.ledger(&params.address);
+                let account = Account {
+                    address: params.address.clone(),
+                    balance: Bones::new(ledger.balance),
+                    nonce: ledger.nonce,
+                    speculative_nonce: ledger.speculative_nonce,
+                    dc_balance: ledger.dc_balance,
+                    dc_nonce: 0,
+                    sec_balance: 0,
+                    sec_nonce: 0,
+                };
+                to_value(account)
+            }
+            Method::OraclePriceCurrent => to_value(OraclePrice {
+                price: self.oracle_price,
+                height: self.height,
+            }),
+            Method::OraclePriceGet { params } => to_value(OraclePrice {
+                price: self.oracle_price,
+                height: params.height,
+            }),
+            Method::ChainVarGet { params } => self
+                .chain_vars
+                .get(&params.name)
+                .cloned()
+                .ok_or_else(|| not_found("chain var")),
+            Method::ChainVarsGet => to_value(&self.chain_vars),
+            Method::PeerBook { .. } => to_value(vec![Peer {
+                address: "/p2p/mock".to_string(),
+                name: Some("mock-node".to_string()),
+                listen_addrs: vec!["/ip4/127.0.0.1/tcp/44158".to_string()],
+                nat: Some("none".to_string()),
+                last_updated: None,
+            }]),
+            Method::InfoSummary => to_value(NodeInfo {
+                name: "mock-node".to_string(),
+                height: self.height,
+                version: Some(env!("CARGO_PKG_VERSION").to_string()),
+                sync_height: Some(self.height),
+            }),
+        }
+    }
+
+    fn submit(
+        &mut self,
+        payer: Address,
+        payments: Vec<(Address, Bones)>,
+        nonce: Option<u64>,
+    ) -> MethodResult {
+        self.unlocked_wallet(&payer)?;
+        if payments.iter().any(|(_, bones)| bones.get() == 0) {
+            return Err(invalid_params("payment amount must be positive"));
+        }
+        let used = |nonce| {
+            self.pending
+                .values()
+                .any(|pending| pending.payer == payer && pending.nonce == nonce)
+        };
+        if let Some(nonce) = nonce {
+            if used(nonce) || nonce <= self.ledger(&payer).nonce {
+                return Err(invalid_params(&format!("nonce {} already used", nonce)));
+            }
+        }
+        let ledger = self.ledger(&payer);
+        let nonce = nonce.unwrap_or(ledger.speculative_nonce + 1);
+        ledger.speculative_nonce = ledger.speculative_nonce.max(nonce);
+
+        self.next_hash += 1;
+        let hash = format!("mock-txn-{}", self.next_hash);
+        self.pending.insert(
+            hash.clone(),
+            Pending {
+                payer,
+                payments,
+                nonce,
+                submitted: self.height,
+            },
+        );
+        Ok(json!({ "hash": hash }))
+    }
+
+    fn clear_pending(&mut self) {
+        let mut due: Vec<(u64, String)> = self
+            .pending
+            .iter()
+            .filter(|(_, pending)| pending.submitted + self.clear_after <= self.height)
+            .map(|(hash, pending)| (pending.nonce, hash.clone()))
+            .collect();
+        // each payer's transactions have to clear in nonce order
+        due.sort();
+        for (_, hash) in due {
+            if let Some(pending) = self.pending.remove(&hash) {
+                let status = self.apply(&hash, pending);
+                self.statuses.insert(hash, status);
+            }
+        }
+    }
+
+    fn apply(&mut self, hash: &str, pending: Pending) -> PendingStatus {
+        let total: u64 = pending.payments.iter().map(|(_, bones)| bones.get()).sum();
+        let payer = self.ledger(&pending.payer);
+        if pending.nonce != payer.nonce + 1 {
+            return PendingStatus::Failed("invalid nonce".to_string());
+        }
+        if payer.balance < total {
+            return PendingStatus::Failed("insufficient balance".to_string());
+        }
+        payer.balance -= total;
+        payer.nonce = pending.nonce;
+        for (payee, bones) in &pending.payments {
+            self.ledger(payee).balance += bones.get();
+        }
+
+        let height = self.height;
+        let mut fields = serde_json::Map::new();
+        fields.insert("payer".to_string(), json!(pending.payer));
+        fields.insert("nonce".to_string(), json!(pending.nonce));
+        fields.insert(
+            "payments".to_string(),
+            pending
+                .payments
+                .iter()
+                .map(|(payee, bones)| json!({"payee": payee, "amount": bones}))
+                .collect(),
+        );
+        self.transactions.insert(
+            hash.to_string(),
+            Transaction {
+                hash: hash.to_string(),
+                kind: "payment_v2".to_string(),
+                height: Some(height),
+                fields,
+            },
+        );
+        self.blocks
+            .entry(height)
+            .or_default()
+            .push(hash.to_string());
+        PendingStatus::Cleared
+    }
+}
@@ -0,0 +1,121 @@
+use super::{
+    Address, Bones, Client, Error, Method, MultiPaymentParams, PaymentParams, PaymentResult, Result,
+};
+use std::{collections::HashMap, sync::MutexGuard};
+
+/// Whether a payer has used a nonce, and so whether a payment with that
+/// nonce would be a duplicate
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NonceStatus {
+    /// No transaction with the nonce has been submitted
+    Unused,
+    /// A transaction with the nonce is waiting to clear. The hash is known
+    /// if it was submitted through this client.
+    Pending { hash: Option<String> },
+    /// A transaction with the nonce has cleared
+    Cleared { hash: Option<String> },
+}
+
+/// Nonces handed out and payments submitted through a client, shared by its
+/// clones
+#[derive(Default)]
+pub(crate) struct Nonces {
+    reserved: HashMap<Address, u64>,
+    submitted: HashMap<(Address, u64), String>,
+}
+
+impl Client {
+    fn nonces(&self) -> MutexGuard<'_, Nonces> {
+        self.nonces.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Reserves the next nonce for a payer. The node's speculative nonce is
+    /// checked each time, so payments submitted elsewhere are accounted for,
+    /// and nonces already handed out by this client are never reused.
+    pub async fn next_nonce(&self, address: &Address) -> Result<u64> {
+        let account = self.get_account(address).await?;
+        let mut nonces = self.nonces();
+        let reserved = nonces.reserved.entry(address.clone()).or_default();
+        *reserved = (*reserved).max(account.speculative_nonce) + 1;
+        Ok(*reserved)
+    }
+
+    /// Looks up whether the payer has used `nonce`
+    pub async fn payment_status(&self, address: &Address, nonce: u64) -> Result<NonceStatus> {
+        let account = self.get_account(address).await?;
+        let hash = self
+            .nonces()
+            .submitted
+            .get(&(address.clone(), nonce))
+            .cloned();
+        Ok(if account.nonce >= nonce {
+            NonceStatus::Cleared { hash }
+        } else if account.speculative_nonce >= nonce {
+            NonceStatus::Pending { hash }
+        } else {
+            NonceStatus::Unused
+        })
+    }
+
+    /// Pays with an explicit nonce, e.g. one from `next_nonce` that the
+    /// caller stored before paying. If the nonce has been used already,
+    /// typically by an earlier attempt that timed out, nothing is sent and
+    /// `Error::NonceUsed` is returned, so retrying never pays twice.
+    pub async fn pay_with_nonce(
+        &self,
+        address: &Address,
+        payee: &Address,
+        bones: Bones,
+        nonce: u64,
+    ) -> Result<String> {
+        self.submit_with_nonce(
+            address,
+            nonce,
+            Method::WalletPay {
+                params: PaymentParams {
+                    address: address.clone(),
+                    payee: payee.clone(),
+                    bones,
+                    nonce: Some(nonce),
+                },
+            },
+        )
+        .await
+    }
+
+    /// Multi-payee version of `pay_with_nonce`
+    pub async fn pay_multi_with_nonce(
+        &self,
+        address: &Address,
+        payments: Vec<(Address, Bones)>,
+        nonce: u64,
+    ) -> Result<String> {
+        let mut params = MultiPaymentParams::new(address.clone(), payments)?;
+        params.nonce = Some(nonce);
+        self.submit_with_nonce(address, nonce, Method::WalletPayMulti { params })
+            .await
+    }
+
+    async fn submit_with_nonce(
+        &self,
+        address: &Address,
+        nonce: u64,
+        method: Method,
+    ) -> Result<String> {
+        match self.payment_status(address, nonce).await? {
+            NonceStatus::Unused => (),
+            NonceStatus::Pending { hash } | NonceStatus::Cleared { hash } => {
+                return Err(Error::NonceUsed {
+                    address: address.clone(),
+                    nonce,
+                    hash,
+                })
+            }
+        }
+        let result: PaymentResult = self.call(method).await?;
+        self.nonces()
+            .submitted
+            .insert((address.clone(), nonce), result.hash.clone());
+        Ok(result.hash)
+    }
+}
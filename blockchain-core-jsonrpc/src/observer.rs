@@ -0,0 +1,122 @@
+use super::{Error, Result};
+use serde_json::{json, Value};
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    path::Path,
+    sync::Mutex,
+    time::Duration,
+};
+
+/// Placeholder written in place of secrets
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Keys whose values are replaced by `redact`
+const SECRET_KEYS: &[&str] = &["password"];
+
+/// Sees every request a `Client` sends and every response it receives,
+/// including each retry. Requests are passed as JSON, secrets included, so
+/// implementations that store or print them should `redact` them first.
+pub trait Observer: Send + Sync {
+    /// Called before a request or batch is handed to the transport
+    fn on_request(&self, _request: &Value) {}
+
+    /// Called with the raw response body, or the error that took its place,
+    /// and the time the exchange took
+    fn on_response(
+        &self,
+        _request: &Value,
+        _response: std::result::Result<&str, &Error>,
+        _elapsed: Duration,
+    ) {
+    }
+}
+
+/// Returns a copy of a request with every secret replaced by `REDACTED`
+pub fn redact(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| {
+                    let value = if SECRET_KEYS.contains(&key.as_str()) {
+                        Value::from(REDACTED)
+                    } else {
+                        redact(value)
+                    };
+                    (key.clone(), value)
+                })
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(values.iter().map(redact).collect()),
+        value => value.clone(),
+    }
+}
+
+/// Logs each exchange through the `log` crate at debug level, and failed
+/// ones at warn level, with secrets redacted
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LogObserver;
+
+impl Observer for LogObserver {
+    fn on_request(&self, request: &Value) {
+        log::debug!("json-rpc request: {}", redact(request));
+    }
+
+    fn on_response(
+        &self,
+        request: &Value,
+        response: std::result::Result<&str, &Error>,
+        elapsed: Duration,
+    ) {
+        match response {
+            Ok(body) => log::debug!("json-rpc response after {:?}: {}", elapsed, body.trim_end()),
+            Err(e) => log::warn!(
+                "json-rpc request {} failed after {:?}: {}",
+                redact(request),
+                elapsed,
+                e
+            ),
+        }
+    }
+}
+
+/// Appends each exchange to a file as one line of JSON, with secrets
+/// redacted, for later inspection or replay
+pub struct RecordObserver {
+    file: Mutex<File>,
+}
+
+impl RecordObserver {
+    /// Opens `path` for appending, creating it if needed
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<RecordObserver> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(RecordObserver {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl Observer for RecordObserver {
+    fn on_response(
+        &self,
+        request: &Value,
+        response: std::result::Result<&str, &Error>,
+        elapsed: Duration,
+    ) {
+        let mut record = json!({
+            "request": redact(request),
+            "elapsed_ms": elapsed.as_millis() as u64,
+        });
+        match response {
+            Ok(body) => {
+                record["response"] =
+                    serde_json::from_str(body).unwrap_or_else(|_| Value::from(body))
+            }
+            Err(e) => record["error"] = Value::from(e.to_string()),
+        }
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        // an observer has no way to report failures; losing a record must
+        // not fail the request it describes
+        let _ = writeln!(file, "{}", record);
+    }
+}
@@ -0,0 +1,234 @@
+use super::{
+    Account, Address, Block, Bones, Client, NonceStatus, PendingStatus, Result, Secret, Transaction,
+};
+use futures::future::join_all;
+use std::{
+    future::Future,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+/// Clients for several nodes used as one. Read-only calls are spread over
+/// the nodes that answer and are within `max_lag` blocks of the highest
+/// one, failing over to the next node when one cannot be reached. Calls
+/// that use a wallet always go to the wallet node, the first one given.
+#[derive(Clone)]
+pub struct ClientPool {
+    clients: Vec<Client>,
+    max_lag: u64,
+    refresh_interval: Duration,
+    state: Arc<Mutex<PoolState>>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Node {
+    height: Option<u64>,
+    down: bool,
+}
+
+struct PoolState {
+    nodes: Vec<Node>,
+    next: usize,
+    refreshed: Option<Instant>,
+}
+
+macro_rules! pinned {
+    ($($(#[$attr:meta])* fn $name:ident(&self $(, $arg:ident: $ty:ty)*) -> $ret:ty;)*) => {
+        $(
+            $(#[$attr])*
+            pub async fn $name(&self $(, $arg: $ty)*) -> Result<$ret> {
+                self.wallet().$name($($arg),*).await
+            }
+        )*
+    };
+}
+
+impl ClientPool {
+    /// Creates a pool whose wallet node is `wallet`
+    pub fn new(wallet: Client, others: Vec<Client>) -> ClientPool {
+        let mut clients = vec![wallet];
+        clients.extend(others);
+        ClientPool {
+            state: Arc::new(Mutex::new(PoolState {
+                nodes: vec![Node::default(); clients.len()],
+                next: 0,
+                refreshed: None,
+            })),
+            clients,
+            max_lag: 5,
+            refresh_interval: Duration::from_secs(10),
+        }
+    }
+
+    /// Number of blocks a node may be behind the highest one and still be
+    /// used for reads. Defaults to 5.
+    pub fn max_lag(mut self, blocks: u64) -> ClientPool {
+        self.max_lag = blocks;
+        self
+    }
+
+    /// How often the heights of all nodes are checked, before a read.
+    /// Defaults to 10 seconds.
+    pub fn refresh_interval(mut self, interval: Duration) -> ClientPool {
+        self.refresh_interval = interval;
+        self
+    }
+
+    /// The client of the node holding the wallet
+    pub fn wallet(&self) -> &Client {
+        &self.clients[0]
+    }
+
+    fn state(&self) -> MutexGuard<'_, PoolState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Asks every node for its height, marking the ones that do not answer
+    /// as down until the next refresh
+    pub async fn refresh(&self) {
+        let heights = join_all(self.clients.iter().map(Client::get_height)).await;
+        let mut state = self.state();
+        for (node, height) in state.nodes.iter_mut().zip(heights) {
+            *node = match height {
+                Ok(height) => Node {
+                    height: Some(height as u64),
+                    down: false,
+                },
+                Err(e) => Node {
+                    height: node.height,
+                    down: e.is_transient(),
+                },
+            };
+        }
+        state.refreshed = Some(Instant::now());
+    }
+
+    /// Indexes of the nodes that are up and not lagging, in the order
+    /// they are used for reads
+    pub fn healthy(&self) -> Vec<usize> {
+        self.order(false).0
+    }
+
+    /// Healthy nodes, rotated to the next one in turn when `advance` is
+    /// set, and the others
+    fn order(&self, advance: bool) -> (Vec<usize>, Vec<usize>) {
+        let mut state = self.state();
+        let best = state
+            .nodes
+            .iter()
+            .filter(|node| !node.down)
+            .filter_map(|node| node.height)
+            .max();
+        let (mut healthy, others): (Vec<usize>, Vec<usize>) =
+            (0..state.nodes.len()).partition(|&index| {
+                let node = state.nodes[index];
+                !node.down
+                    && !matches!((node.height, best), (Some(height), Some(best)) if height + self.max_lag < best)
+            });
+        if advance && !healthy.is_empty() {
+            let start = state.next % healthy.len();
+            healthy.rotate_left(start);
+            state.next = state.next.wrapping_add(1);
+        }
+        (healthy, others)
+    }
+
+    fn mark_down(&self, index: usize) {
+        self.state().nodes[index].down = true;
+    }
+
+    /// Runs a read-only call on the next healthy node, moving on to the
+    /// others while nodes cannot be reached. Lagging and down nodes are
+    /// only tried once all healthy ones failed.
+    async fn read<T, F, Fut>(&self, call: F) -> Result<T>
+    where
+        F: Fn(Client) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let stale = match self.state().refreshed {
+            Some(refreshed) => refreshed.elapsed() >= self.refresh_interval,
+            None => true,
+        };
+        if stale {
+            self.refresh().await;
+        }
+        let (healthy, others) = self.order(true);
+        let mut error = None;
+        for index in healthy.into_iter().chain(others) {
+            match call(self.clients[index].clone()).await {
+                Err(e) if e.is_transient() => {
+                    self.mark_down(index);
+                    error = Some(e);
+                }
+                result => return result,
+            }
+        }
+        Err(error.expect("pool has at least one node"))
+    }
+
+    pub async fn get_height(&self) -> Result<usize> {
+        self.read(|client| async move { client.get_height().await })
+            .await
+    }
+
+    /// Pending transactions are only known to the node they were submitted
+    /// to, so the wallet node is asked as well before reporting `NotFound`
+    pub async fn pending_transaction_status(&self, hash: String) -> Result<PendingStatus> {
+        let status = self
+            .read(|client| {
+                let hash = hash.clone();
+                async move { client.pending_transaction_status(hash).await }
+            })
+            .await?;
+        match status {
+            PendingStatus::NotFound => self.wallet().pending_transaction_status(hash).await,
+            status => Ok(status),
+        }
+    }
+
+    pub async fn get_block_by_height(&self, height: u64) -> Result<Block> {
+        self.read(|client| async move { client.get_block_by_height(height).await })
+            .await
+    }
+
+    pub async fn get_transaction(&self, hash: String) -> Result<Transaction> {
+        self.read(|client| {
+            let hash = hash.clone();
+            async move { client.get_transaction(hash).await }
+        })
+        .await
+    }
+
+    pub async fn get_account(&self, address: &Address) -> Result<Account> {
+        self.read(|client| {
+            let address = address.clone();
+            async move { client.get_account(&address).await }
+        })
+        .await
+    }
+
+    pinned! {
+        fn create_wallet(&self, password: Secret) -> Address;
+        fn list_wallets(&self) -> Vec<Address>;
+        fn unlock_wallet(&self, address: &Address, password: &Secret) -> bool;
+        fn lock_wallet(&self, address: &Address) -> bool;
+        fn pay(&self, address: &Address, payee: &Address, bones: Bones) -> String;
+        fn pay_multi(&self, address: &Address, payments: Vec<(Address, Bones)>) -> String;
+        fn pay_with_nonce(
+            &self,
+            address: &Address,
+            payee: &Address,
+            bones: Bones,
+            nonce: u64
+        ) -> String;
+        fn pay_multi_with_nonce(
+            &self,
+            address: &Address,
+            payments: Vec<(Address, Bones)>,
+            nonce: u64
+        ) -> String;
+        // nonces are reserved and tracked by the wallet node's client
+        fn next_nonce(&self, address: &Address) -> u64;
+        fn payment_status(&self, address: &Address, nonce: u64) -> NonceStatus;
+    }
+}
@@ -0,0 +1,87 @@
+use super::{Error, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use zeroize::Zeroize;
+
+/// A password or other secret string. The memory holding it is zeroed on
+/// drop and it prints as `[REDACTED]`, so it cannot leak through a log line.
+/// It serializes as the plain string since the node needs to see it.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(secret: String) -> Secret {
+        Secret(secret)
+    }
+
+    /// Reads the secret from an environment variable
+    pub fn from_env(name: &str) -> Result<Secret> {
+        std::env::var(name)
+            .map(Secret)
+            .map_err(|_| Error::MissingEnv(name.to_string()))
+    }
+
+    /// Reads the secret from an inherited file descriptor until it is
+    /// closed, dropping a trailing newline. The descriptor is consumed.
+    #[cfg(unix)]
+    pub fn from_fd(fd: std::os::unix::io::OwnedFd) -> Result<Secret> {
+        use std::{fs::File, io::Read};
+        let mut file = File::from(fd);
+        let mut secret = Secret(String::new());
+        file.read_to_string(&mut secret.0)?;
+        if secret.0.ends_with('\n') {
+            secret.0.pop();
+            if secret.0.ends_with('\r') {
+                secret.0.pop();
+            }
+        }
+        Ok(secret)
+    }
+
+    /// The secret itself; keep the borrow short-lived
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl From<String> for Secret {
+    fn from(secret: String) -> Secret {
+        Secret(secret)
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(secret: &str) -> Secret {
+        Secret(secret.to_string())
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+impl Serialize for Secret {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Secret {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Secret)
+    }
+}
@@ -0,0 +1,30 @@
+use super::Result;
+use async_trait::async_trait;
+
+mod http;
+mod replay;
+mod stdio;
+#[cfg(unix)]
+mod unix;
+
+pub use http::HttpTransport;
+pub use replay::ReplayTransport;
+pub use stdio::StdioTransport;
+#[cfg(unix)]
+pub use unix::UnixTransport;
+
+/// Carries serialized JSON-RPC requests to a node and brings back the raw
+/// response. Implementations only move bytes; envelopes, ids, timeouts and
+/// retries are handled by `Client`.
+#[async_trait]
+pub trait Transport: Send + Sync {
+    /// Sends a request or batch and returns the body of the response
+    async fn send(&self, request: String) -> Result<String>;
+}
+
+#[async_trait]
+impl<T: Transport + ?Sized> Transport for std::sync::Arc<T> {
+    async fn send(&self, request: String) -> Result<String> {
+        (**self).send(request).await
+    }
+}
@@ -0,0 +1,48 @@
+use super::Transport;
+use crate::{types::ErrorResponse, Result};
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, CONTENT_TYPE};
+
+/// Posts requests to a node's HTTP endpoint
+pub struct HttpTransport {
+    pub(crate) address: String,
+    pub(crate) client: reqwest::Client,
+    pub(crate) headers: HeaderMap,
+    pub(crate) basic_auth: Option<(String, Option<String>)>,
+}
+
+impl HttpTransport {
+    pub fn new(address: String) -> HttpTransport {
+        HttpTransport {
+            address,
+            client: reqwest::Client::new(),
+            headers: HeaderMap::new(),
+            basic_auth: None,
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for HttpTransport {
+    async fn send(&self, request: String) -> Result<String> {
+        let mut request = self
+            .client
+            .post(&self.address)
+            .headers(self.headers.clone())
+            .header(CONTENT_TYPE, "application/json")
+            .body(request);
+        if let Some((username, password)) = &self.basic_auth {
+            request = request.basic_auth(username, password.as_ref());
+        }
+        let response = request.send().await?;
+        let status = response.error_for_status_ref().map(|_| ());
+        let body = response.text().await?;
+        // a JSON-RPC error object takes precedence over the HTTP status since
+        // some nodes report errors with a non-200 status code
+        if status.is_err() && serde_json::from_str::<ErrorResponse>(&body).is_ok() {
+            return Ok(body);
+        }
+        status?;
+        Ok(body)
+    }
+}
@@ -0,0 +1,119 @@
+use super::Transport;
+use crate::{redact, Error, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::{
+    collections::{HashMap, VecDeque},
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+    sync::Mutex,
+};
+
+/// Answers requests from exchanges recorded by `RecordObserver`, so tests
+/// can run offline and deterministically. Requests are matched on method and
+/// params, ignoring their random ids and any redacted secrets. A request
+/// recorded several times is answered with each recorded response in turn.
+pub struct ReplayTransport {
+    responses: Mutex<HashMap<String, VecDeque<Recorded>>>,
+}
+
+struct Recorded {
+    request: Value,
+    response: Value,
+}
+
+impl ReplayTransport {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<ReplayTransport> {
+        ReplayTransport::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Reads records, one JSON object per line. Records of failed exchanges
+    /// have no response and are skipped.
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<ReplayTransport> {
+        let mut responses: HashMap<String, VecDeque<Recorded>> = HashMap::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut record: Value =
+                serde_json::from_str(&line).map_err(|e| Error::decode(e, &line))?;
+            let response = record["response"].take();
+            if response.is_null() {
+                continue;
+            }
+            let request = record["request"].take();
+            responses
+                .entry(key(&request))
+                .or_default()
+                .push_back(Recorded { request, response });
+        }
+        Ok(ReplayTransport {
+            responses: Mutex::new(responses),
+        })
+    }
+
+    /// Whether every recorded response has been served
+    pub fn is_exhausted(&self) -> bool {
+        self.responses
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .all(VecDeque::is_empty)
+    }
+}
+
+/// Identifies a request or batch by its methods and params alone
+fn key(request: &Value) -> String {
+    let strip = |request: &Value| json!([request["method"], redact(&request["params"])]);
+    match request {
+        Value::Array(requests) => Value::Array(requests.iter().map(strip).collect()),
+        request => strip(request),
+    }
+    .to_string()
+}
+
+/// Maps the ids of a recorded request or batch to those of a new one
+fn ids(recorded: &Value, request: &Value) -> HashMap<String, Value> {
+    match (recorded, request) {
+        (Value::Array(recorded), Value::Array(requests)) => recorded
+            .iter()
+            .zip(requests)
+            .map(|(recorded, request)| (recorded["id"].to_string(), request["id"].clone()))
+            .collect(),
+        (recorded, request) => vec![(recorded["id"].to_string(), request["id"].clone())]
+            .into_iter()
+            .collect(),
+    }
+}
+
+fn rewrite_ids(response: &mut Value, ids: &HashMap<String, Value>) {
+    match response {
+        Value::Array(responses) => responses.iter_mut().for_each(|r| rewrite_ids(r, ids)),
+        Value::Object(response) => {
+            if let Some(id) = response.get("id").and_then(|id| ids.get(&id.to_string())) {
+                response.insert("id".to_string(), id.clone());
+            }
+        }
+        _ => (),
+    }
+}
+
+#[async_trait]
+impl Transport for ReplayTransport {
+    async fn send(&self, request: String) -> Result<String> {
+        let request: Value = serde_json::from_str(&request).map_err(Error::Encode)?;
+        let key = key(&request);
+        let recorded = self
+            .responses
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_mut(&key)
+            .and_then(VecDeque::pop_front)
+            .ok_or(Error::NotRecorded(key))?;
+        let mut response = recorded.response;
+        rewrite_ids(&mut response, &ids(&recorded.request, &request));
+        Ok(response.to_string())
+    }
+}
@@ -0,0 +1,113 @@
+use super::Transport;
+use crate::Result;
+use async_trait::async_trait;
+use std::{io, process::Stdio};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    process::{Child, ChildStdin, ChildStdout, Command},
+    sync::Mutex,
+};
+
+/// Talks to a child process over its stdin and stdout, one line of JSON per
+/// request and per response. Requests are sent one at a time and the child
+/// is expected to answer them in order.
+///
+/// A call cancelled part way, e.g. by `ClientBuilder::timeout`, leaves its
+/// request and response in the pipes. The next call finishes writing the
+/// request if needed and skips the late response, so responses never get
+/// out of step with requests.
+pub struct StdioTransport {
+    io: Mutex<ChildIo>,
+}
+
+struct ChildIo {
+    // kept so the child is killed when the transport is dropped
+    _child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    /// Bytes of requests not written yet
+    unsent: Vec<u8>,
+    /// Start of a response line read so far
+    partial: Vec<u8>,
+    /// Requests sent or queued whose response has not been read
+    pending: usize,
+}
+
+impl StdioTransport {
+    /// Spawns the command with piped stdin and stdout
+    pub fn spawn(command: std::process::Command) -> Result<StdioTransport> {
+        let mut child = Command::from(command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+        let stdin = child.stdin.take().ok_or_else(closed)?;
+        let stdout = child.stdout.take().ok_or_else(closed)?;
+        Ok(StdioTransport {
+            io: Mutex::new(ChildIo {
+                _child: child,
+                stdin,
+                stdout: BufReader::new(stdout),
+                unsent: Vec::new(),
+                partial: Vec::new(),
+                pending: 0,
+            }),
+        })
+    }
+}
+
+fn closed() -> io::Error {
+    io::Error::from(io::ErrorKind::BrokenPipe)
+}
+
+// Every await below is cancel safe: progress is recorded in `ChildIo` as
+// soon as it is made, so a dropped call loses nothing.
+impl ChildIo {
+    async fn write_unsent(&mut self) -> io::Result<()> {
+        while !self.unsent.is_empty() {
+            let written = self.stdin.write(&self.unsent).await?;
+            if written == 0 {
+                return Err(closed());
+            }
+            self.unsent.drain(..written);
+        }
+        self.stdin.flush().await
+    }
+
+    async fn read_line(&mut self) -> io::Result<String> {
+        loop {
+            if let Some(end) = self.partial.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = self.partial.drain(..=end).collect();
+                return String::from_utf8(line)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e));
+            }
+            let buf = self.stdout.fill_buf().await?;
+            if buf.is_empty() {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            let read = buf.len();
+            self.partial.extend_from_slice(buf);
+            self.stdout.consume(read);
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for StdioTransport {
+    async fn send(&self, request: String) -> Result<String> {
+        let mut io = self.io.lock().await;
+        io.unsent.extend_from_slice(request.as_bytes());
+        io.unsent.push(b'\n');
+        io.pending += 1;
+        io.write_unsent().await?;
+
+        // responses to earlier, cancelled requests come first
+        loop {
+            let response = io.read_line().await?;
+            io.pending -= 1;
+            if io.pending == 0 {
+                return Ok(response);
+            }
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use super::Transport;
+use crate::Result;
+use async_trait::async_trait;
+use std::{io, path::PathBuf};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::UnixStream,
+};
+
+/// Talks to a node over a Unix domain socket, so access can be restricted
+/// with file permissions instead of exposing an HTTP port. Each request is
+/// written as a single line of JSON on a fresh connection and answered with
+/// a single line.
+pub struct UnixTransport {
+    path: PathBuf,
+}
+
+impl UnixTransport {
+    pub fn new<P: Into<PathBuf>>(path: P) -> UnixTransport {
+        UnixTransport { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl Transport for UnixTransport {
+    async fn send(&self, request: String) -> Result<String> {
+        let stream = UnixStream::connect(&self.path).await?;
+        let (reader, mut writer) = stream.into_split();
+        writer.write_all(request.as_bytes()).await?;
+        writer.write_all(b"\n").await?;
+        writer.flush().await?;
+
+        let mut response = String::new();
+        if BufReader::new(reader).read_line(&mut response).await? == 0 {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        Ok(response)
+    }
+}
@@ -1,9 +1,43 @@
-use super::Method;
-use serde::{Deserialize, Serialize};
+use super::{Address, Bones, Error, JsonRpcError, Method, Result, Secret};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{collections::HashSet, fmt};
+
+pub(crate) const JSONRPC_VERSION: &str = "2.0";
+
+/// JSON-RPC 2.0 response envelope, carrying either a `result` or an `error`
+#[derive(Clone, Deserialize, Debug)]
+pub(crate) struct Response<T> {
+    pub jsonrpc: String,
+    #[serde(default)]
+    pub id: serde_json::Value,
+    pub result: Option<T>,
+    pub error: Option<JsonRpcError>,
+}
+
+impl<T> Response<T> {
+    /// Checks the envelope against the request it answers and unpacks it
+    pub fn into_result(self, id: &str) -> Result<T> {
+        if self.jsonrpc != JSONRPC_VERSION {
+            return Err(Error::InvalidVersion(self.jsonrpc));
+        }
+        // errors for requests the node could not parse carry a null id
+        if !(self.error.is_some() && self.id.is_null()) && self.id.as_str() != Some(id) {
+            return Err(Error::IdMismatch {
+                expected: id.to_string(),
+                received: self.id,
+            });
+        }
+        match (self.result, self.error) {
+            (_, Some(error)) => Err(error.into()),
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(Error::MissingResult),
+        }
+    }
+}
 
 #[derive(Clone, Deserialize, Debug)]
-pub(crate) struct Response {
-    pub result: String,
+pub(crate) struct ErrorResponse {
+    pub error: JsonRpcError,
 }
 
 #[derive(Clone, Deserialize, Debug, Serialize)]
@@ -16,37 +50,244 @@ pub(crate) struct JsonRpc {
 impl JsonRpc {
     pub fn new(request: Method) -> JsonRpc {
         JsonRpc {
-            jsonrpc: "2.0".into(),
+            jsonrpc: JSONRPC_VERSION.into(),
             id: rand::random::<usize>().to_string(),
             method: request,
         }
     }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
 }
 
 #[derive(Clone, Deserialize, Debug, Serialize)]
-pub(crate) struct UnlockParams {
-    pub address: String,
-    pub password: String,
+pub struct UnlockParams {
+    pub address: Address,
+    pub password: Secret,
 }
 
 #[derive(Clone, Deserialize, Debug, Serialize)]
-pub(crate) struct LockParams {
-    pub address: String,
+pub struct LockParams {
+    pub address: Address,
 }
 
 #[derive(Clone, Deserialize, Debug, Serialize)]
-pub(crate) struct Password {
-    pub password: String,
+pub struct Password {
+    pub password: Secret,
 }
 
 #[derive(Clone, Deserialize, Debug, Serialize)]
-pub(crate) struct PaymentParams {
-    pub address: String,
-    pub payee: String,
-    pub bones: usize,
+pub struct PaymentParams {
+    pub address: Address,
+    pub payee: Address,
+    pub bones: Bones,
+    /// Nonce for the transaction; the node picks the next one when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+}
+
+#[derive(Clone, Deserialize, Debug, Serialize)]
+pub struct Payment {
+    pub payee: Address,
+    pub bones: Bones,
 }
 
 #[derive(Clone, Deserialize, Debug, Serialize)]
-pub(crate) struct PendingTxnStatus {
+pub struct MultiPaymentParams {
+    pub address: Address,
+    pub payments: Vec<Payment>,
+    /// Nonce for the transaction; the node picks the next one when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+}
+
+impl MultiPaymentParams {
+    /// Builds the parameters, rejecting an empty list or a repeated payee
+    pub fn new(address: Address, payments: Vec<(Address, Bones)>) -> Result<Self> {
+        if payments.is_empty() {
+            return Err(Error::NoPayees);
+        }
+        let mut payees = HashSet::new();
+        for (payee, _) in &payments {
+            if !payees.insert(payee) {
+                return Err(Error::DuplicatePayee(payee.clone()));
+            }
+        }
+        Ok(MultiPaymentParams {
+            address,
+            payments: payments
+                .into_iter()
+                .map(|(payee, bones)| Payment { payee, bones })
+                .collect(),
+            nonce: None,
+        })
+    }
+}
+
+#[derive(Clone, Deserialize, Debug, Serialize)]
+pub struct PendingTxnStatus {
     pub hash: String,
 }
+
+/// Status of a submitted transaction as reported by the node
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PendingStatus {
+    Pending,
+    Cleared,
+    NotFound,
+    /// The node rejected the transaction; carries the reason it reported
+    Failed(String),
+}
+
+impl fmt::Display for PendingStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PendingStatus::Pending => f.write_str("pending"),
+            PendingStatus::Cleared => f.write_str("cleared"),
+            PendingStatus::NotFound => f.write_str("not_found"),
+            PendingStatus::Failed(reason) => f.write_str(reason),
+        }
+    }
+}
+
+impl From<String> for PendingStatus {
+    fn from(status: String) -> Self {
+        match status.as_str() {
+            "pending" => PendingStatus::Pending,
+            "cleared" => PendingStatus::Cleared,
+            "not_found" => PendingStatus::NotFound,
+            _ => PendingStatus::Failed(status),
+        }
+    }
+}
+
+impl Serialize for PendingStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for PendingStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(deserializer).map(PendingStatus::from)
+    }
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub(crate) struct PaymentResult {
+    pub hash: String,
+}
+
+/// Identifies a block either by its height or by its hash
+#[derive(Clone, Deserialize, Debug, Serialize)]
+#[serde(untagged)]
+pub enum BlockParams {
+    Height { height: u64 },
+    Hash { hash: String },
+}
+
+#[derive(Clone, Deserialize, Debug, Serialize)]
+pub struct TransactionParams {
+    pub hash: String,
+}
+
+#[derive(Clone, Deserialize, Debug, Serialize)]
+pub struct AccountParams {
+    pub address: Address,
+}
+
+#[derive(Clone, Deserialize, Debug, Serialize)]
+pub struct OraclePriceParams {
+    pub height: u64,
+}
+
+#[derive(Clone, Deserialize, Debug, Serialize)]
+pub struct ChainVarParams {
+    pub name: String,
+}
+
+#[derive(Clone, Deserialize, Debug, Serialize)]
+pub struct PeerBookParams {
+    pub addr: String,
+}
+
+#[derive(Clone, Deserialize, Debug, Serialize)]
+pub struct Block {
+    pub hash: String,
+    pub height: u64,
+    pub prev_hash: String,
+    pub time: u64,
+    #[serde(default)]
+    pub transactions: Vec<BlockTransaction>,
+}
+
+#[derive(Clone, Deserialize, Debug, Serialize)]
+pub struct BlockTransaction {
+    pub hash: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+/// A transaction as returned by the node. Fields beyond the common ones
+/// depend on the transaction type and are kept in `fields`.
+#[derive(Clone, Deserialize, Debug, Serialize)]
+pub struct Transaction {
+    pub hash: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub height: Option<u64>,
+    #[serde(flatten)]
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Clone, Deserialize, Debug, Serialize)]
+pub struct Account {
+    pub address: Address,
+    pub balance: Bones,
+    pub nonce: u64,
+    pub speculative_nonce: u64,
+    #[serde(default)]
+    pub dc_balance: u64,
+    #[serde(default)]
+    pub dc_nonce: u64,
+    #[serde(default)]
+    pub sec_balance: u64,
+    #[serde(default)]
+    pub sec_nonce: u64,
+}
+
+/// HNT price in USD, with 8 decimal places
+#[derive(Clone, Deserialize, Debug, Serialize)]
+pub struct OraclePrice {
+    pub price: u64,
+    pub height: u64,
+}
+
+#[derive(Clone, Deserialize, Debug, Serialize)]
+pub struct Peer {
+    pub address: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub listen_addrs: Vec<String>,
+    #[serde(default)]
+    pub nat: Option<String>,
+    #[serde(default)]
+    pub last_updated: Option<u64>,
+}
+
+#[derive(Clone, Deserialize, Debug, Serialize)]
+pub struct NodeInfo {
+    pub name: String,
+    pub height: u64,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub sync_height: Option<u64>,
+}
@@ -0,0 +1,109 @@
+use super::{Address, Bones, Client, Error, Result, Secret};
+use std::time::Duration;
+use tokio::{runtime::Handle, task::JoinHandle};
+
+/// A wallet unlocked on the node for as long as this handle lives. The
+/// wallet is locked again by `close`, or when the handle is dropped, so it
+/// does not stay unlocked when a payout fails half way.
+///
+/// Only `close().await` is guaranteed to lock the wallet. On a
+/// multi-threaded runtime, with the default `multi-thread` feature, dropping
+/// the handle blocks until the lock request is answered, which covers a
+/// `#[tokio::main]` that returns early with `?`. On a current-thread
+/// runtime the lock is only spawned, and is lost if the runtime shuts down
+/// before it runs.
+pub struct UnlockedWallet {
+    client: Client,
+    address: Address,
+    auto_lock: Option<JoinHandle<()>>,
+    closed: bool,
+}
+
+impl Client {
+    /// Unlocks the wallet and returns a handle that locks it again
+    pub async fn unlocked_wallet(
+        &self,
+        address: &Address,
+        password: &Secret,
+    ) -> Result<UnlockedWallet> {
+        if !self.unlock_wallet(address, password).await? {
+            return Err(Error::UnlockFailed(address.clone()));
+        }
+        Ok(UnlockedWallet {
+            client: self.clone(),
+            address: address.clone(),
+            auto_lock: None,
+            closed: false,
+        })
+    }
+
+    /// Like `unlocked_wallet`, but also locks the wallet once `timeout` has
+    /// passed even if the handle is still alive
+    pub async fn unlocked_wallet_for(
+        &self,
+        address: &Address,
+        password: &Secret,
+        timeout: Duration,
+    ) -> Result<UnlockedWallet> {
+        let mut wallet = self.unlocked_wallet(address, password).await?;
+        let client = self.clone();
+        let address = address.clone();
+        wallet.auto_lock = Some(tokio::spawn(async move {
+            tokio::time::sleep(timeout).await;
+            let _ = client.lock_wallet(&address).await;
+        }));
+        Ok(wallet)
+    }
+}
+
+impl UnlockedWallet {
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
+
+    pub async fn pay(&self, payee: &Address, bones: Bones) -> Result<String> {
+        self.client.pay(&self.address, payee, bones).await
+    }
+
+    pub async fn pay_multi(&self, payments: Vec<(Address, Bones)>) -> Result<String> {
+        self.client.pay_multi(&self.address, payments).await
+    }
+
+    /// Locks the wallet and waits for the node to confirm it
+    pub async fn close(mut self) -> Result<()> {
+        self.closed = true;
+        if let Some(auto_lock) = self.auto_lock.take() {
+            auto_lock.abort();
+        }
+        self.client.lock_wallet(&self.address).await?;
+        Ok(())
+    }
+}
+
+impl Drop for UnlockedWallet {
+    fn drop(&mut self) {
+        if let Some(auto_lock) = self.auto_lock.take() {
+            auto_lock.abort();
+        }
+        if self.closed {
+            return;
+        }
+        // without a runtime there is no way to reach the node from here
+        if let Ok(handle) = Handle::try_current() {
+            let client = self.client.clone();
+            let address = self.address.clone();
+            let lock = async move {
+                let _ = client.lock_wallet(&address).await;
+            };
+            // lock before returning, as the runtime may be shutting down
+            #[cfg(feature = "multi-thread")]
+            {
+                if handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread {
+                    tokio::task::block_in_place(|| handle.block_on(lock));
+                    return;
+                }
+            }
+            handle.spawn(lock);
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use blockchain_core_jsonrpc::{Address, Error, ADDRESS_VERSION};
+
+const ADDRESS: &str = "13oodQVVBTZ4bxRMXyzXtEq4VspPGczfi3aKQWvwHHh5aBBy6vs";
+
+fn invalid(s: &str) -> bool {
+    matches!(s.parse::<Address>(), Err(Error::InvalidAddress(_)))
+}
+
+#[test]
+fn valid_address_round_trips() {
+    let address: Address = ADDRESS.parse().unwrap();
+    assert_eq!(address.to_string(), ADDRESS);
+    let key = address.to_key_bytes();
+    assert_eq!(key.len(), 33);
+    assert_eq!(Address::from_key_bytes(&key).unwrap(), address);
+}
+
+#[test]
+fn rejects_bad_checksum() {
+    let mut s = ADDRESS.to_string();
+    let last = s.pop().unwrap();
+    s.push(if last == 's' { 't' } else { 's' });
+    assert!(invalid(&s));
+}
+
+#[test]
+fn rejects_wrong_version() {
+    let key = ADDRESS.parse::<Address>().unwrap().to_key_bytes();
+    let s = bs58::encode(&key)
+        .with_check_version(ADDRESS_VERSION + 1)
+        .into_string();
+    assert!(invalid(&s));
+}
+
+#[test]
+fn rejects_wrong_key_length() {
+    let key = ADDRESS.parse::<Address>().unwrap().to_key_bytes();
+    for key in &[&key[..32], &[key.as_slice(), &[0]].concat()[..]] {
+        let s = bs58::encode(key)
+            .with_check_version(ADDRESS_VERSION)
+            .into_string();
+        assert!(invalid(&s));
+        assert!(Address::from_key_bytes(key).is_err());
+    }
+}
+
+#[test]
+fn rejects_non_base58() {
+    assert!(invalid(""));
+    assert!(invalid("0OIl"));
+    assert!(invalid(&ADDRESS[1..]));
+}
@@ -0,0 +1,29 @@
+use blockchain_core_jsonrpc::{blocking::Client, mock::MockNode, Bones, PendingStatus, Secret};
+
+mod common;
+use common::hnt;
+
+#[test]
+fn payment_without_runtime() {
+    let node = MockNode::new();
+    let payer = node.add_wallet("secret", hnt(10));
+    let payee = node.add_account(Bones::default());
+    let client = Client::with_client(node.client()).unwrap();
+
+    assert_eq!(client.list_wallets().unwrap(), vec![payer.clone()]);
+    assert!(client
+        .unlock_wallet(&payer, &Secret::from("secret"))
+        .unwrap());
+    let hash = client.pay(&payer, &payee, hnt(4)).unwrap();
+    assert_eq!(
+        client.pending_transaction_status(hash.clone()).unwrap(),
+        PendingStatus::Pending
+    );
+    node.advance_blocks(1);
+    assert_eq!(
+        client.pending_transaction_status(hash).unwrap(),
+        PendingStatus::Cleared
+    );
+    assert_eq!(client.get_account(&payee).unwrap().balance, hnt(4));
+    assert_eq!(client.get_height().unwrap(), 2);
+}
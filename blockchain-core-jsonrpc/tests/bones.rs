@@ -0,0 +1,50 @@
+use blockchain_core_jsonrpc::{Bones, Error, BONES_PER_HNT};
+
+fn parse(s: &str) -> Option<u64> {
+    match Bones::parse_hnt(s) {
+        Ok(bones) => Some(bones.get()),
+        Err(Error::InvalidAmount(amount)) => {
+            assert_eq!(amount, s);
+            None
+        }
+        Err(e) => panic!("unexpected error {:?}", e),
+    }
+}
+
+#[test]
+fn parses_decimal_hnt() {
+    assert_eq!(parse("12.5"), Some(1_250_000_000));
+    assert_eq!(parse("0.00000001"), Some(1));
+    assert_eq!(parse(".5"), Some(50_000_000));
+    assert_eq!(parse("1."), Some(BONES_PER_HNT));
+    assert_eq!(parse("007"), Some(7 * BONES_PER_HNT));
+}
+
+#[test]
+fn rejects_malformed_amounts() {
+    for s in &["", ".", "+1", "-1", "1.2.3", "1,5", " 1", "1e8", "abc"] {
+        assert_eq!(parse(s), None, "{:?}", s);
+    }
+}
+
+#[test]
+fn rejects_more_than_eight_decimals() {
+    assert_eq!(parse("0.000000001"), None);
+    assert_eq!(parse("1.000000000"), None);
+}
+
+#[test]
+fn rejects_overflow() {
+    let max_hnt = u64::MAX / BONES_PER_HNT;
+    assert!(parse(&max_hnt.to_string()).is_some());
+    assert_eq!(parse(&(max_hnt + 1).to_string()), None);
+    assert_eq!(parse("99999999999999999999999"), None);
+    assert!(Bones::from_hnt(max_hnt + 1).is_err());
+}
+
+#[test]
+fn formats_as_hnt() {
+    let bones = Bones::parse_hnt("1.5").unwrap();
+    assert_eq!(bones.to_hnt_string(), "1.50000000");
+    assert_eq!(Bones::parse_hnt(&bones.to_hnt_string()).unwrap(), bones);
+}
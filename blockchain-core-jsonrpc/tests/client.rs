@@ -0,0 +1,357 @@
+use blockchain_core_jsonrpc::{
+    mock::MockNode, Bones, Client, Error, JsonRpcError, Method, PendingStatus, PendingTxnStatus,
+    Secret,
+};
+use std::time::Duration;
+
+mod common;
+use common::hnt;
+
+#[tokio::test]
+async fn wallet_lifecycle() {
+    let node = MockNode::new();
+    let client = node.client();
+
+    let address = client.create_wallet(Secret::from("secret")).await.unwrap();
+    assert_eq!(client.list_wallets().await.unwrap(), vec![address.clone()]);
+
+    assert!(!client
+        .unlock_wallet(&address, &Secret::from("wrong"))
+        .await
+        .unwrap());
+    assert!(!node.is_unlocked(&address));
+    assert!(client
+        .unlock_wallet(&address, &Secret::from("secret"))
+        .await
+        .unwrap());
+    assert!(node.is_unlocked(&address));
+    assert!(client.lock_wallet(&address).await.unwrap());
+    assert!(!node.is_unlocked(&address));
+}
+
+#[tokio::test]
+async fn payment_clears_after_blocks() {
+    let node = MockNode::new();
+    node.set_clear_after(3);
+    let payer = node.add_wallet("secret", hnt(10));
+    let payee = node.add_account(Bones::default());
+    let client = node.client();
+
+    client
+        .unlock_wallet(&payer, &Secret::from("secret"))
+        .await
+        .unwrap();
+    let hash = client.pay(&payer, &payee, hnt(4)).await.unwrap();
+    assert_eq!(
+        client
+            .pending_transaction_status(hash.clone())
+            .await
+            .unwrap(),
+        PendingStatus::Pending
+    );
+
+    node.advance_blocks(2);
+    assert_eq!(
+        client
+            .pending_transaction_status(hash.clone())
+            .await
+            .unwrap(),
+        PendingStatus::Pending
+    );
+    node.advance_blocks(1);
+    assert_eq!(
+        client
+            .pending_transaction_status(hash.clone())
+            .await
+            .unwrap(),
+        PendingStatus::Cleared
+    );
+    assert_eq!(node.balance(&payer), hnt(6));
+    assert_eq!(node.balance(&payee), hnt(4));
+
+    let transaction = client.get_transaction(hash.clone()).await.unwrap();
+    assert_eq!(transaction.height, Some(node.height()));
+    let block = client.get_block_by_height(node.height()).await.unwrap();
+    assert_eq!(block.transactions[0].hash, hash);
+}
+
+#[tokio::test]
+async fn wait_for_transaction() {
+    let node = MockNode::new();
+    let payer = node.add_wallet("secret", hnt(10));
+    let payee = node.add_account(Bones::default());
+    let client = node.client();
+    client
+        .unlock_wallet(&payer, &Secret::from("secret"))
+        .await
+        .unwrap();
+    let hash = client.pay(&payer, &payee, hnt(1)).await.unwrap();
+
+    let miner = node.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        miner.advance_blocks(1);
+    });
+    let height = client
+        .wait_for_transaction(hash, Duration::from_secs(5), Duration::from_millis(10))
+        .await
+        .unwrap();
+    assert_eq!(height, Some(node.height()));
+}
+
+#[tokio::test]
+async fn wait_for_transaction_not_indexed_yet() {
+    let node = MockNode::new();
+    let payer = node.add_wallet("secret", hnt(10));
+    let payee = node.add_account(Bones::default());
+    let client = node.client();
+    client
+        .unlock_wallet(&payer, &Secret::from("secret"))
+        .await
+        .unwrap();
+    let hash = client.pay(&payer, &payee, hnt(1)).await.unwrap();
+    node.advance_blocks(1);
+    let cleared = node.height();
+    node.advance_blocks(2);
+    node.fail_next(
+        "transaction_get",
+        JsonRpcError::new(JsonRpcError::NOT_FOUND, "transaction not found"),
+    );
+
+    // the lookup is retried until the transaction is indexed
+    let height = client
+        .wait_for_transaction(hash, Duration::from_secs(5), Duration::from_millis(10))
+        .await
+        .unwrap();
+    assert_eq!(height, Some(cleared));
+}
+
+#[tokio::test]
+async fn wait_for_transaction_without_lookup() {
+    let node = MockNode::new();
+    let payer = node.add_wallet("secret", hnt(10));
+    let payee = node.add_account(Bones::default());
+    let client = node.client();
+    client
+        .unlock_wallet(&payer, &Secret::from("secret"))
+        .await
+        .unwrap();
+    let hash = client.pay(&payer, &payee, hnt(1)).await.unwrap();
+    node.advance_blocks(3);
+    node.fail_next(
+        "transaction_get",
+        JsonRpcError::new(JsonRpcError::METHOD_NOT_FOUND, "method not found"),
+    );
+
+    // the height is not made up from the current one
+    let height = client
+        .wait_for_transaction(hash, Duration::from_secs(5), Duration::from_millis(10))
+        .await
+        .unwrap();
+    assert_eq!(height, None);
+}
+
+#[tokio::test]
+async fn wait_for_failed_transaction() {
+    let node = MockNode::new();
+    let payer = node.add_wallet("secret", hnt(1));
+    let payee = node.add_account(Bones::default());
+    let client = node.client();
+    client
+        .unlock_wallet(&payer, &Secret::from("secret"))
+        .await
+        .unwrap();
+    let hash = client.pay(&payer, &payee, hnt(2)).await.unwrap();
+    node.advance_blocks(1);
+
+    match client
+        .wait_for_transaction(hash, Duration::from_secs(5), Duration::from_millis(10))
+        .await
+    {
+        Err(Error::TransactionFailed { reason, .. }) => assert_eq!(reason, "insufficient balance"),
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn wait_for_transaction_times_out() {
+    let node = MockNode::new();
+    let payer = node.add_wallet("secret", hnt(1));
+    let payee = node.add_account(Bones::default());
+    let client = node.client();
+    client
+        .unlock_wallet(&payer, &Secret::from("secret"))
+        .await
+        .unwrap();
+    let hash = client.pay(&payer, &payee, hnt(1)).await.unwrap();
+
+    let result = client
+        .wait_for_transaction(hash, Duration::from_millis(50), Duration::from_millis(10))
+        .await;
+    assert!(matches!(result, Err(Error::Timeout)), "{:?}", result);
+}
+
+#[tokio::test]
+async fn pay_from_locked_wallet() {
+    let node = MockNode::new();
+    let payer = node.add_wallet("secret", hnt(1));
+    let payee = node.add_account(Bones::default());
+
+    match node.client().pay(&payer, &payee, hnt(1)).await {
+        Err(Error::JsonRpc(error)) => assert_eq!(error.code, JsonRpcError::WALLET_LOCKED),
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn pay_multi() {
+    let node = MockNode::new();
+    let payer = node.add_wallet("secret", hnt(10));
+    let first = node.add_account(Bones::default());
+    let second = node.add_account(Bones::default());
+    let client = node.client();
+    client
+        .unlock_wallet(&payer, &Secret::from("secret"))
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        client.pay_multi(&payer, vec![]).await,
+        Err(Error::NoPayees)
+    ));
+    assert!(matches!(
+        client
+            .pay_multi(&payer, vec![(first.clone(), hnt(1)), (first.clone(), hnt(2))])
+            .await,
+        Err(Error::DuplicatePayee(payee)) if payee == first
+    ));
+    // rejected payments never reach the node
+    assert!(!node.requests().contains(&"wallet_pay_multi".to_string()));
+
+    client
+        .pay_multi(
+            &payer,
+            vec![(first.clone(), hnt(1)), (second.clone(), hnt(2))],
+        )
+        .await
+        .unwrap();
+    node.advance_blocks(1);
+    assert_eq!(node.balance(&payer), hnt(7));
+    assert_eq!(node.balance(&first), hnt(1));
+    assert_eq!(node.balance(&second), hnt(2));
+}
+
+#[tokio::test]
+async fn injected_error() {
+    let node = MockNode::new();
+    node.fail_next(
+        "block_height",
+        JsonRpcError::new(JsonRpcError::INTERNAL_ERROR, "boom"),
+    );
+    let client = node.client();
+
+    match client.get_height().await {
+        Err(Error::JsonRpc(error)) => assert_eq!(error.message, "boom"),
+        other => panic!("unexpected {:?}", other),
+    }
+    assert_eq!(client.get_height().await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn batch_with_partial_failure() {
+    let node = MockNode::new();
+    let payer = node.add_wallet("secret", hnt(1));
+    let payee = node.add_account(Bones::default());
+    let client = node.client();
+    client
+        .unlock_wallet(&payer, &Secret::from("secret"))
+        .await
+        .unwrap();
+    let hash = client.pay(&payer, &payee, hnt(1)).await.unwrap();
+    node.fail_next(
+        "pending_transaction_status",
+        JsonRpcError::new(JsonRpcError::INTERNAL_ERROR, "boom"),
+    );
+
+    let status = |hash: &str| Method::PendingTransactionStatus {
+        params: PendingTxnStatus {
+            hash: hash.to_string(),
+        },
+    };
+    let results = client
+        .batch::<PendingStatus>(vec![status("unknown"), status(&hash), status("unknown")])
+        .await
+        .unwrap();
+    assert!(matches!(results[0], Err(Error::JsonRpc(_))));
+    assert_eq!(results[1].as_ref().unwrap(), &PendingStatus::Pending);
+    assert_eq!(results[2].as_ref().unwrap(), &PendingStatus::NotFound);
+}
+
+#[tokio::test]
+async fn retries_idempotent_methods_only() {
+    let node = MockNode::new();
+    let payer = node.add_wallet("secret", hnt(1));
+    let payee = node.add_account(Bones::default());
+    let client = Client::builder(String::new())
+        .transport(node.clone())
+        .retries(3, Duration::from_millis(1))
+        .build();
+
+    node.fail_transport(2);
+    assert_eq!(client.get_height().await.unwrap(), 1);
+
+    client
+        .unlock_wallet(&payer, &Secret::from("secret"))
+        .await
+        .unwrap();
+    node.fail_transport(1);
+    let result = client.pay(&payer, &payee, hnt(1)).await;
+    assert!(matches!(result, Err(Error::Io(_))), "{:?}", result);
+    assert!(!node.requests().contains(&"wallet_pay".to_string()));
+}
+
+#[tokio::test]
+async fn read_only_methods() {
+    let node = MockNode::new();
+    node.advance_blocks(4);
+    let account = node.add_account(hnt(3));
+    let client = node.client();
+
+    assert_eq!(client.get_height().await.unwrap(), 5);
+    assert_eq!(client.get_account(&account).await.unwrap().balance, hnt(3));
+    assert_eq!(client.get_oracle_price().await.unwrap().height, 5);
+    assert_eq!(client.get_oracle_price_at(3).await.unwrap().height, 3);
+    assert_eq!(
+        client
+            .get_chain_var("txn_fee_multiplier".to_string())
+            .await
+            .unwrap(),
+        5000
+    );
+    assert!(client
+        .get_chain_vars()
+        .await
+        .unwrap()
+        .contains_key("dc_payload_size"));
+    let block = client.get_block_by_height(3).await.unwrap();
+    assert_eq!(
+        client.get_block_by_hash(block.hash).await.unwrap().height,
+        3
+    );
+    assert_eq!(client.get_peer_book().await.unwrap().len(), 1);
+    assert_eq!(client.get_node_info().await.unwrap().height, 5);
+}
+
+#[tokio::test]
+async fn default_node_matches_new() {
+    let node = MockNode::default();
+    let client = node.client();
+    assert_eq!(client.get_height().await.unwrap(), 1);
+    assert_eq!(
+        client
+            .get_chain_var("txn_fee_multiplier".to_string())
+            .await
+            .unwrap(),
+        5000
+    );
+}
@@ -0,0 +1,5 @@
+use blockchain_core_jsonrpc::Bones;
+
+pub fn hnt(hnt: u64) -> Bones {
+    Bones::from_hnt(hnt).unwrap()
+}
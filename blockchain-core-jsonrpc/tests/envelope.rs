@@ -0,0 +1,75 @@
+use async_trait::async_trait;
+use blockchain_core_jsonrpc::{Client, Error, JsonRpcError, Result, Transport};
+use serde_json::{json, Value};
+
+/// Answers every request with an envelope built from the request's id
+struct Stub(fn(Value) -> Value);
+
+#[async_trait]
+impl Transport for Stub {
+    async fn send(&self, request: String) -> Result<String> {
+        let request: Value = serde_json::from_str(&request).unwrap();
+        Ok((self.0)(request["id"].clone()).to_string())
+    }
+}
+
+fn client(respond: fn(Value) -> Value) -> Client {
+    Client::with_transport(Stub(respond))
+}
+
+#[tokio::test]
+async fn accepts_matching_envelope() {
+    let client = client(|id| json!({ "jsonrpc": "2.0", "id": id, "result": 7 }));
+    assert_eq!(client.get_height().await.unwrap(), 7);
+}
+
+#[tokio::test]
+async fn rejects_wrong_id() {
+    let client = client(|_| json!({ "jsonrpc": "2.0", "id": "other", "result": 7 }));
+    match client.get_height().await {
+        Err(Error::IdMismatch { received, .. }) => assert_eq!(received, json!("other")),
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn rejects_wrong_version() {
+    let client = client(|id| json!({ "jsonrpc": "1.0", "id": id, "result": 7 }));
+    match client.get_height().await {
+        Err(Error::InvalidVersion(version)) => assert_eq!(version, "1.0"),
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn rejects_empty_envelope() {
+    let client = client(|id| json!({ "jsonrpc": "2.0", "id": id }));
+    assert!(matches!(
+        client.get_height().await,
+        Err(Error::MissingResult)
+    ));
+}
+
+#[tokio::test]
+async fn error_with_null_id_is_reported() {
+    let client = client(|_| {
+        json!({
+            "jsonrpc": "2.0",
+            "id": null,
+            "error": { "code": JsonRpcError::PARSE_ERROR, "message": "parse error" },
+        })
+    });
+    match client.get_height().await {
+        Err(Error::JsonRpc(error)) => assert_eq!(error.code, JsonRpcError::PARSE_ERROR),
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn result_with_null_id_is_rejected() {
+    let client = client(|_| json!({ "jsonrpc": "2.0", "id": null, "result": 7 }));
+    assert!(matches!(
+        client.get_height().await,
+        Err(Error::IdMismatch { .. })
+    ));
+}
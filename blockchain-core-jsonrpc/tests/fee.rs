@@ -0,0 +1,92 @@
+use blockchain_core_jsonrpc::{mock::MockNode, Bones, PaymentIssue};
+
+mod common;
+use common::hnt;
+
+#[tokio::test]
+async fn fee_is_paid_from_dc_when_possible() {
+    let node = MockNode::new();
+    let payer = node.add_wallet("secret", hnt(10));
+    let payee = node.add_account(Bones::default());
+    let client = node.client();
+
+    // 7 payload units of 24 bytes at the default multiplier of 5000,
+    // burnt at $1 per HNT
+    let estimate = client.estimate_pay(&payer, &payee, hnt(4)).await.unwrap();
+    assert!(estimate.is_valid());
+    assert_eq!(estimate.fee_dc, 35_000);
+    assert_eq!(estimate.fee, Bones::parse_hnt("0.35").unwrap());
+    assert_eq!(estimate.balance, Bones::parse_hnt("5.65").unwrap());
+
+    node.set_dc_balance(&payer, 35_000);
+    let estimate = client.estimate_pay(&payer, &payee, hnt(4)).await.unwrap();
+    assert_eq!(estimate.fee, Bones::default());
+    assert_eq!(estimate.balance, hnt(6));
+
+    // nothing was submitted
+    assert!(!node.requests().iter().any(|method| method == "wallet_pay"));
+    assert_eq!(node.balance(&payer), hnt(10));
+}
+
+#[tokio::test]
+async fn multi_payment_is_validated() {
+    let node = MockNode::new();
+    node.set_oracle_price(200_000_000);
+    let payer = node.add_wallet("secret", hnt(5));
+    let first = node.add_account(Bones::default());
+    let second = node.add_account(Bones::default());
+    let client = node.client();
+
+    let estimate = client
+        .estimate_pay_multi(
+            &payer,
+            vec![(first.clone(), hnt(2)), (second.clone(), hnt(2))],
+        )
+        .await
+        .unwrap();
+    assert!(estimate.is_valid());
+    assert_eq!(estimate.fee_dc, 45_000);
+    assert_eq!(estimate.fee, Bones::parse_hnt("0.225").unwrap());
+    assert_eq!(estimate.amount, hnt(4));
+
+    let estimate = client
+        .estimate_pay_multi(
+            &payer,
+            vec![(first.clone(), hnt(5)), (first.clone(), Bones::default())],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        estimate.issues,
+        vec![
+            PaymentIssue::DuplicatePayee(first.clone()),
+            PaymentIssue::ZeroAmount(first),
+            PaymentIssue::InsufficientBalance {
+                required: Bones::parse_hnt("5.225").unwrap(),
+                available: hnt(5),
+            },
+        ]
+    );
+    assert_eq!(estimate.balance, Bones::default());
+
+    let estimate = client.estimate_pay_multi(&payer, vec![]).await.unwrap();
+    assert_eq!(estimate.issues, vec![PaymentIssue::NoPayees]);
+}
+
+#[tokio::test]
+async fn missing_fee_multiplier_is_reported() {
+    let node = MockNode::new();
+    node.set_chain_var("txn_fee_multiplier", serde_json::Value::Null);
+    let payer = node.add_wallet("secret", hnt(10));
+    let payee = node.add_account(Bones::default());
+    let client = node.client();
+
+    let estimate = client.estimate_pay(&payer, &payee, hnt(4)).await.unwrap();
+    assert!(!estimate.is_valid());
+    assert_eq!(
+        estimate.issues,
+        vec![PaymentIssue::MissingChainVar(
+            "txn_fee_multiplier".to_string()
+        )]
+    );
+}
@@ -0,0 +1,43 @@
+use blockchain_core_jsonrpc::{mock::MockNode, NewHeight};
+use futures::{pin_mut, StreamExt};
+use std::time::Duration;
+
+const INTERVAL: Duration = Duration::from_millis(10);
+
+#[tokio::test]
+async fn reports_each_height_once_with_gaps() {
+    let node = MockNode::new();
+    let heights = node.client().heights(INTERVAL, INTERVAL * 8);
+    pin_mut!(heights);
+
+    let first = heights.next().await.unwrap().unwrap();
+    assert_eq!(
+        first,
+        NewHeight {
+            height: 1,
+            previous: None
+        }
+    );
+    node.advance_blocks(1);
+    let next = heights.next().await.unwrap().unwrap();
+    assert_eq!((next.height, next.skipped()), (2, 0));
+    node.advance_blocks(3);
+    let next = heights.next().await.unwrap().unwrap();
+    assert_eq!(next.previous, Some(2));
+    assert_eq!((next.height, next.skipped()), (5, 2));
+}
+
+#[tokio::test]
+async fn keeps_polling_while_unreachable() {
+    let node = MockNode::new();
+    let heights = node.client().heights(INTERVAL, INTERVAL * 8);
+    pin_mut!(heights);
+
+    assert_eq!(heights.next().await.unwrap().unwrap().height, 1);
+    node.fail_transport(2);
+    node.advance_blocks(1);
+    assert!(heights.next().await.unwrap().unwrap_err().is_transient());
+    assert!(heights.next().await.unwrap().unwrap_err().is_transient());
+    let next = heights.next().await.unwrap().unwrap();
+    assert_eq!((next.height, next.previous), (2, Some(1)));
+}
@@ -0,0 +1,105 @@
+use blockchain_core_jsonrpc::{mock::MockNode, Bones, Error, NonceStatus, Secret};
+
+mod common;
+use common::hnt;
+
+#[tokio::test]
+async fn retried_payment_is_not_sent_twice() {
+    let node = MockNode::new();
+    let payer = node.add_wallet("secret", hnt(10));
+    let payee = node.add_account(Bones::default());
+    let client = node.client();
+    client
+        .unlock_wallet(&payer, &Secret::from("secret"))
+        .await
+        .unwrap();
+
+    let nonce = client.next_nonce(&payer).await.unwrap();
+    assert_eq!(nonce, 1);
+    let hash = client
+        .pay_with_nonce(&payer, &payee, hnt(3), nonce)
+        .await
+        .unwrap();
+
+    match client.pay_with_nonce(&payer, &payee, hnt(3), nonce).await {
+        Err(Error::NonceUsed {
+            nonce: 1,
+            hash: Some(used),
+            ..
+        }) => assert_eq!(used, hash),
+        other => panic!("unexpected result {:?}", other),
+    }
+    node.advance_blocks(1);
+    match client.pay_with_nonce(&payer, &payee, hnt(3), nonce).await {
+        Err(Error::NonceUsed { .. }) => (),
+        other => panic!("unexpected result {:?}", other),
+    }
+    assert_eq!(node.balance(&payee), hnt(3));
+    assert_eq!(
+        node.requests()
+            .iter()
+            .filter(|method| *method == "wallet_pay")
+            .count(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn nonce_status_follows_payment() {
+    let node = MockNode::new();
+    let payer = node.add_wallet("secret", hnt(10));
+    let payee = node.add_account(Bones::default());
+    let client = node.client();
+    client
+        .unlock_wallet(&payer, &Secret::from("secret"))
+        .await
+        .unwrap();
+
+    // payments made without an explicit nonce still count
+    client.pay(&payer, &payee, hnt(1)).await.unwrap();
+    assert_eq!(
+        client.payment_status(&payer, 1).await.unwrap(),
+        NonceStatus::Pending { hash: None }
+    );
+    assert_eq!(
+        client.payment_status(&payer, 2).await.unwrap(),
+        NonceStatus::Unused
+    );
+
+    let first = client.next_nonce(&payer).await.unwrap();
+    let second = client.next_nonce(&payer).await.unwrap();
+    assert_eq!((first, second), (2, 3));
+    let hash = client
+        .pay_multi_with_nonce(&payer, vec![(payee.clone(), hnt(2))], first)
+        .await
+        .unwrap();
+    node.advance_blocks(1);
+    assert_eq!(
+        client.payment_status(&payer, first).await.unwrap(),
+        NonceStatus::Cleared { hash: Some(hash) }
+    );
+    assert_eq!(
+        client.payment_status(&payer, second).await.unwrap(),
+        NonceStatus::Unused
+    );
+    assert_eq!(node.balance(&payee), hnt(3));
+}
+
+#[tokio::test]
+async fn payments_clear_in_nonce_order() {
+    let node = MockNode::new();
+    let payer = node.add_wallet("secret", hnt(20));
+    let payee = node.add_account(Bones::default());
+    let client = node.client();
+    client
+        .unlock_wallet(&payer, &Secret::from("secret"))
+        .await
+        .unwrap();
+
+    for _ in 0..12 {
+        client.pay(&payer, &payee, hnt(1)).await.unwrap();
+    }
+    node.advance_blocks(1);
+    assert_eq!(node.balance(&payee), hnt(12));
+    assert_eq!(client.get_account(&payer).await.unwrap().nonce, 12);
+}
@@ -0,0 +1,73 @@
+use blockchain_core_jsonrpc::{
+    mock::MockNode, Bones, Client, Error, Observer, RecordObserver, Secret,
+};
+use serde_json::Value;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+#[derive(Clone, Default)]
+struct Counter {
+    exchanges: Arc<Mutex<Vec<(String, bool)>>>,
+}
+
+impl Observer for Counter {
+    fn on_response(&self, request: &Value, response: Result<&str, &Error>, _elapsed: Duration) {
+        let method = request["method"].as_str().unwrap_or_default().to_string();
+        self.exchanges
+            .lock()
+            .unwrap()
+            .push((method, response.is_ok()));
+    }
+}
+
+#[tokio::test]
+async fn observer_sees_retries() {
+    let node = MockNode::new();
+    let counter = Counter::default();
+    let client = Client::builder(String::new())
+        .transport(node.clone())
+        .observer(counter.clone())
+        .retries(1, Duration::from_millis(1))
+        .build();
+
+    node.fail_transport(1);
+    client.get_height().await.unwrap();
+    assert_eq!(
+        *counter.exchanges.lock().unwrap(),
+        vec![
+            ("block_height".to_string(), false),
+            ("block_height".to_string(), true)
+        ]
+    );
+}
+
+#[tokio::test]
+async fn record_observer_redacts_passwords() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("exchanges.jsonl");
+    let node = MockNode::new();
+    let payer = node.add_wallet("hunter2", Bones::default());
+    let client = Client::builder(String::new())
+        .transport(node)
+        .observer(RecordObserver::create(&path).unwrap())
+        .build();
+
+    client
+        .unlock_wallet(&payer, &Secret::from("hunter2"))
+        .await
+        .unwrap();
+    client.get_height().await.unwrap();
+
+    let recorded = std::fs::read_to_string(&path).unwrap();
+    assert!(!recorded.contains("hunter2"));
+    let records: Vec<Value> = recorded
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0]["request"]["params"]["password"], "[REDACTED]");
+    assert_eq!(records[0]["response"]["result"], true);
+    assert_eq!(records[1]["response"]["result"], 1);
+}
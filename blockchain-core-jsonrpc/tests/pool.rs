@@ -0,0 +1,106 @@
+use blockchain_core_jsonrpc::{
+    mock::MockNode, Bones, ClientPool, NonceStatus, PendingStatus, Secret,
+};
+
+fn pool(nodes: &[MockNode]) -> ClientPool {
+    ClientPool::new(
+        nodes[0].client(),
+        nodes[1..].iter().map(MockNode::client).collect(),
+    )
+}
+
+fn height_requests(node: &MockNode) -> usize {
+    node.requests()
+        .iter()
+        .filter(|method| *method == "block_height")
+        .count()
+}
+
+#[tokio::test]
+async fn reads_are_spread_over_nodes() {
+    let nodes = vec![MockNode::new(), MockNode::new(), MockNode::new()];
+    let pool = pool(&nodes);
+
+    for _ in 0..3 {
+        pool.get_height().await.unwrap();
+    }
+    // one request each to check heights, then one read each
+    for node in &nodes {
+        assert_eq!(height_requests(node), 2);
+    }
+}
+
+#[tokio::test]
+async fn lagging_and_unreachable_nodes_are_skipped() {
+    let nodes = vec![MockNode::new(), MockNode::new(), MockNode::new()];
+    nodes[0].advance_blocks(10);
+    nodes[1].advance_blocks(10);
+    let pool = pool(&nodes).max_lag(2);
+
+    pool.refresh().await;
+    assert_eq!(pool.healthy(), vec![0, 1]);
+
+    nodes[1].fail_transport(1);
+    for _ in 0..4 {
+        assert_eq!(pool.get_height().await.unwrap(), 11);
+    }
+    assert_eq!(pool.healthy(), vec![0]);
+    assert_eq!(height_requests(&nodes[2]), 1);
+
+    pool.refresh().await;
+    assert_eq!(pool.healthy(), vec![0, 1]);
+}
+
+#[tokio::test]
+async fn wallet_calls_stay_on_wallet_node() {
+    let nodes = vec![MockNode::new(), MockNode::new()];
+    let payer = nodes[0].add_wallet("secret", Bones::from_hnt(10).unwrap());
+    let payee = nodes[0].add_account(Bones::default());
+    let pool = pool(&nodes);
+
+    assert!(pool
+        .unlock_wallet(&payer, &Secret::from("secret"))
+        .await
+        .unwrap());
+    let hash = pool
+        .pay(&payer, &payee, Bones::from_hnt(1).unwrap())
+        .await
+        .unwrap();
+    assert!(!nodes[1]
+        .requests()
+        .iter()
+        .any(|method| method.starts_with("wallet_")));
+
+    // whichever node is asked, the wallet node knows the pending payment
+    for _ in 0..2 {
+        assert_eq!(
+            pool.pending_transaction_status(hash.clone()).await.unwrap(),
+            PendingStatus::Pending
+        );
+    }
+}
+
+#[tokio::test]
+async fn nonce_calls_stay_on_wallet_node() {
+    let nodes = vec![MockNode::new(), MockNode::new()];
+    let payer = nodes[0].add_wallet("secret", Bones::from_hnt(10).unwrap());
+    let payee = nodes[0].add_account(Bones::default());
+    let pool = pool(&nodes);
+
+    assert!(pool
+        .unlock_wallet(&payer, &Secret::from("secret"))
+        .await
+        .unwrap());
+    let first = pool.next_nonce(&payer).await.unwrap();
+    let second = pool.next_nonce(&payer).await.unwrap();
+    assert_eq!(second, first + 1);
+    let hash = pool
+        .pay_multi_with_nonce(&payer, vec![(payee, Bones::from_hnt(1).unwrap())], first)
+        .await
+        .unwrap();
+    assert_eq!(
+        pool.payment_status(&payer, first).await.unwrap(),
+        NonceStatus::Pending { hash: Some(hash) }
+    );
+    assert!(nodes[1].requests().is_empty());
+}
@@ -0,0 +1,50 @@
+use blockchain_core_jsonrpc::{Address, Bones, Client, Error, ReplayTransport, Secret};
+use std::{sync::Arc, time::Duration};
+
+const PAYER: &str = "13oodQVVBTZ4bxRMXyzXtEq4VspPGczfi3aKQWvwHHh5aBBy6vs";
+const PAYEE: &str = "147euCrsGqXKp4idSWFBB6mHJKaQ388d1uHvnS4JmztvFD9XWZH";
+
+fn replay() -> ReplayTransport {
+    ReplayTransport::open(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/payment.jsonl"
+    ))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn replay_payment() {
+    let replay = Arc::new(replay());
+    let client = Client::with_transport(replay.clone());
+    let payer: Address = PAYER.parse().unwrap();
+    let payee: Address = PAYEE.parse().unwrap();
+
+    // the recording holds a redacted password, so any password matches
+    let wallet = client
+        .unlocked_wallet(&payer, &Secret::from("anything"))
+        .await
+        .unwrap();
+    let hash = wallet
+        .pay(&payee, Bones::from_hnt(1).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(hash, "mock-txn-1");
+    let height = client
+        .wait_for_transaction(hash, Duration::from_secs(5), Duration::from_millis(1))
+        .await
+        .unwrap();
+    assert_eq!(height, Some(101));
+    wallet.close().await.unwrap();
+    assert!(replay.is_exhausted());
+}
+
+#[tokio::test]
+async fn replay_unrecorded_request() {
+    let client = Client::with_transport(replay());
+    let payer: Address = PAYER.parse().unwrap();
+    let payee: Address = PAYEE.parse().unwrap();
+
+    // a different amount than the one recorded
+    let result = client.pay(&payer, &payee, Bones::new(1)).await;
+    assert!(matches!(result, Err(Error::NotRecorded(_))), "{:?}", result);
+}
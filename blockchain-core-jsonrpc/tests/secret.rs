@@ -0,0 +1,27 @@
+#![cfg(unix)]
+
+use blockchain_core_jsonrpc::Secret;
+use std::{
+    fs::File,
+    io::{Seek, SeekFrom, Write},
+    os::unix::io::OwnedFd,
+};
+
+fn secret_from(contents: &str) -> Secret {
+    let mut file: File = tempfile::tempfile().unwrap();
+    file.write_all(contents.as_bytes()).unwrap();
+    file.seek(SeekFrom::Start(0)).unwrap();
+    Secret::from_fd(OwnedFd::from(file)).unwrap()
+}
+
+#[test]
+fn from_fd_drops_trailing_newline() {
+    assert_eq!(secret_from("hunter2\n").expose(), "hunter2");
+    assert_eq!(secret_from("hunter2\r\n").expose(), "hunter2");
+    assert_eq!(secret_from("hunter2").expose(), "hunter2");
+}
+
+#[test]
+fn from_fd_keeps_inner_newlines() {
+    assert_eq!(secret_from("a\nb\n\n").expose(), "a\nb\n");
+}
@@ -0,0 +1,40 @@
+#![cfg(unix)]
+use blockchain_core_jsonrpc::{Client, Error, StdioTransport};
+use std::{process::Command, time::Duration};
+
+/// Answers each request with its sequence number as the result, waiting
+/// `delay` seconds before answering the first one
+fn counter(delay: &str) -> StdioTransport {
+    let script = r#"
+        n=0
+        while IFS= read -r line; do
+            n=$((n + 1))
+            id=$(printf '%s' "$line" | sed 's/.*"id":"\([^"]*\)".*/\1/')
+            if [ "$n" = 1 ]; then sleep "$0"; fi
+            printf '{"jsonrpc":"2.0","id":"%s","result":%s}\n' "$id" "$n"
+        done
+    "#;
+    let mut command = Command::new("sh");
+    command.arg("-c").arg(script).arg(delay);
+    StdioTransport::spawn(command).unwrap()
+}
+
+#[tokio::test]
+async fn requests_are_answered_in_order() {
+    let client = Client::with_transport(counter("0"));
+    assert_eq!(client.get_height().await.unwrap(), 1);
+    assert_eq!(client.get_height().await.unwrap(), 2);
+    assert_eq!(client.get_height().await.unwrap(), 3);
+}
+
+#[tokio::test]
+async fn late_response_is_skipped_after_timeout() {
+    let client = Client::builder(String::new())
+        .transport(counter("0.6"))
+        .timeout(Duration::from_millis(400))
+        .build();
+    assert!(matches!(client.get_height().await, Err(Error::Timeout)));
+    // the answer to the first request arrives while waiting for this one
+    assert_eq!(client.get_height().await.unwrap(), 2);
+    assert_eq!(client.get_height().await.unwrap(), 3);
+}
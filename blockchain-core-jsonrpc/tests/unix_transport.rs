@@ -0,0 +1,54 @@
+#![cfg(unix)]
+
+use blockchain_core_jsonrpc::{Client, PendingStatus};
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::UnixListener,
+};
+
+/// Answers each line on the socket with a canned result for its method
+async fn serve(listener: UnixListener) {
+    loop {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        let mut line = String::new();
+        BufReader::new(reader).read_line(&mut line).await.unwrap();
+        let request: Value = serde_json::from_str(&line).unwrap();
+        let result = match request["method"].as_str().unwrap() {
+            "block_height" => json!(1234),
+            "pending_transaction_status" => json!("cleared"),
+            _ => Value::Null,
+        };
+        let response = json!({"jsonrpc": "2.0", "id": request["id"], "result": result});
+        writer
+            .write_all(format!("{}\n", response).as_bytes())
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn unix_socket_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("node.sock");
+    tokio::spawn(serve(UnixListener::bind(&path).unwrap()));
+
+    let client = Client::unix(&path);
+    assert_eq!(client.get_height().await.unwrap(), 1234);
+    assert_eq!(
+        client
+            .pending_transaction_status("hash".to_string())
+            .await
+            .unwrap(),
+        PendingStatus::Cleared
+    );
+}
+
+#[tokio::test]
+async fn unix_socket_connect_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let client = Client::unix(dir.path().join("missing.sock"));
+    let error = client.get_height().await.unwrap_err();
+    assert!(error.is_transient(), "{:?}", error);
+}
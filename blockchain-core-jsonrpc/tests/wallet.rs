@@ -0,0 +1,124 @@
+use blockchain_core_jsonrpc::{mock::MockNode, Bones, Error, Secret, UnlockParams};
+use std::time::Duration;
+
+mod common;
+use common::hnt;
+
+#[tokio::test]
+async fn close_locks_wallet() {
+    let node = MockNode::new();
+    let payer = node.add_wallet("secret", hnt(10));
+    let payee = node.add_account(Bones::default());
+    let client = node.client();
+
+    let wallet = client
+        .unlocked_wallet(&payer, &Secret::from("secret"))
+        .await
+        .unwrap();
+    assert!(node.is_unlocked(&payer));
+    wallet.pay(&payee, hnt(1)).await.unwrap();
+    wallet.close().await.unwrap();
+    assert!(!node.is_unlocked(&payer));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn drop_locks_wallet_on_error() {
+    let node = MockNode::new();
+    let payer = node.add_wallet("secret", hnt(10));
+    let client = node.client();
+
+    let result: Result<(), Error> = async {
+        let wallet = client
+            .unlocked_wallet(&payer, &Secret::from("secret"))
+            .await?;
+        wallet.pay_multi(vec![]).await?;
+        wallet.close().await
+    }
+    .await;
+    assert!(matches!(result, Err(Error::NoPayees)));
+    assert!(!node.is_unlocked(&payer));
+}
+
+#[test]
+fn drop_locks_wallet_before_runtime_shuts_down() {
+    let node = MockNode::new();
+    let payer = node.add_wallet("secret", hnt(10));
+    let client = node.client();
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    // like a `#[tokio::main]` returning early with `?`
+    let result: Result<(), Error> = runtime.block_on(async {
+        let wallet = client
+            .unlocked_wallet(&payer, &Secret::from("secret"))
+            .await?;
+        wallet.pay_multi(vec![]).await?;
+        wallet.close().await
+    });
+    runtime.shutdown_background();
+    assert!(matches!(result, Err(Error::NoPayees)));
+    assert!(!node.is_unlocked(&payer));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn drop_in_task_locks_wallet() {
+    let node = MockNode::new();
+    let payer = node.add_wallet("secret", hnt(10));
+    let client = node.client();
+
+    tokio::spawn(async move {
+        let _wallet = client
+            .unlocked_wallet(&payer, &Secret::from("secret"))
+            .await
+            .unwrap();
+    })
+    .await
+    .unwrap();
+    assert!(node.requests().ends_with(&["wallet_lock".to_string()]));
+}
+
+#[tokio::test]
+async fn wrong_password() {
+    let node = MockNode::new();
+    let payer = node.add_wallet("secret", hnt(10));
+
+    let result = node
+        .client()
+        .unlocked_wallet(&payer, &Secret::from("wrong"))
+        .await;
+    assert!(matches!(result, Err(ref e) if e.is_wallet_locked()));
+}
+
+#[tokio::test]
+async fn auto_lock_after_timeout() {
+    let node = MockNode::new();
+    let payer = node.add_wallet("secret", hnt(10));
+    let payee = node.add_account(Bones::default());
+    let client = node.client();
+
+    let wallet = client
+        .unlocked_wallet_for(&payer, &Secret::from("secret"), Duration::from_millis(20))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!node.is_unlocked(&payer));
+    assert!(wallet
+        .pay(&payee, hnt(1))
+        .await
+        .unwrap_err()
+        .is_wallet_locked());
+}
+
+#[test]
+fn password_is_redacted() {
+    let params = UnlockParams {
+        address: MockNode::new().add_account(Bones::default()),
+        password: Secret::from("hunter2"),
+    };
+    assert!(!format!("{:?}", params).contains("hunter2"));
+    assert_eq!(params.password.to_string(), "[REDACTED]");
+    // the node still receives the password itself
+    assert!(serde_json::to_string(&params).unwrap().contains("hunter2"));
+}
@@ -1,12 +1,15 @@
 use super::*;
 use async_trait::async_trait;
 use chrono::{DateTime, NaiveDateTime, Utc};
-use helium_api::{oracle, Dc, Hnt};
+use futures::{pin_mut, StreamExt};
+use helium_api::{accounts, hotspots, oracle, Dc, Hnt};
+use helium_proto::blockchain_txn::Txn;
 use prettytable::{Cell, Row};
+use std::collections::BTreeMap;
 
 #[async_trait]
-pub trait ToRow {
-    async fn to_row(&self, account: &Address, client: &Client) -> Row;
+pub trait ToEntries {
+    async fn to_entries(&self, account: &Address, client: &Client) -> Vec<Entry>;
 }
 
 #[async_trait]
@@ -99,48 +102,16 @@ impl GetDifference for PaymentV2 {
     }
 }
 
-#[async_trait]
-impl GetDifference for RewardsV1 {
-    async fn get_difference(
-        &self,
-        _account: &Address,
-        _client: &Client,
-        _height: u64,
-    ) -> Difference {
-        let mut hnt = Hnt::from(0).get_decimal();
-        // summate rewards for all reward types
-        for reward in &self.proto.rewards {
-            hnt += Hnt::from(reward.amount).get_decimal();
-        }
-
-        Difference {
-            counterparty: Some("Rewards".to_string()),
-            hnt: Hnt::new(hnt),
-            dc: Dc::from(0),
-            fee: 0,
-        }
-    }
-}
-#[async_trait]
-impl GetDifference for RewardsV2 {
-    async fn get_difference(
-        &self,
-        _account: &Address,
-        _client: &Client,
-        _height: u64,
-    ) -> Difference {
-        let mut hnt = Hnt::from(0).get_decimal();
-        // summate rewards for all reward types
-        for reward in &self.proto.rewards {
-            hnt += Hnt::from(reward.amount).get_decimal();
-        }
-
-        Difference {
-            counterparty: Some("Rewards".to_string()),
-            hnt: Hnt::new(hnt),
-            dc: Dc::from(0),
-            fee: 0,
-        }
+/// Name of a reward type, as in `BlockchainTxnRewardV1.Type`
+fn reward_type_name(reward_type: i32) -> String {
+    match reward_type {
+        0 => "Securities".to_string(),
+        1 => "DataCredits".to_string(),
+        2 => "PocChallengees".to_string(),
+        3 => "PocChallengers".to_string(),
+        4 => "PocWitnesses".to_string(),
+        5 => "Consensus".to_string(),
+        other => format!("Type{}", other),
     }
 }
 
@@ -184,89 +155,558 @@ impl GetDifference for TokenBurnV1 {
     }
 }
 
-struct Metadata {
-    height: usize,
-    hash: String,
-    time: usize,
+/// Staking and transaction fees are paid by `payer`, or by the owner when
+/// no separate payer signed the transaction
+fn fee_payer<'a>(payer: &'a [u8], owner: &'a [u8]) -> &'a [u8] {
+    if payer.is_empty() {
+        owner
+    } else {
+        payer
+    }
+}
+
+/// A transaction that involves the account without moving any of its funds
+fn no_difference(counterparty: Option<String>) -> Difference {
+    Difference {
+        counterparty,
+        hnt: Hnt::from(0),
+        dc: Dc::from(0),
+        fee: 0,
+    }
+}
+
+#[async_trait]
+impl GetDifference for AddGatewayV1 {
+    async fn get_difference(
+        &self,
+        account: &Address,
+        _client: &Client,
+        _height: u64,
+    ) -> Difference {
+        let counterparty = Some(Address::from_vec(self.proto.gateway.clone()).to_string());
+        // This account is paying the staking fee for the hotspot
+        if fee_payer(&self.proto.payer, &self.proto.owner) == account.as_vec().as_slice() {
+            Difference {
+                counterparty,
+                hnt: Hnt::from(0),
+                dc: Dc::from(0),
+                fee: self.proto.fee + self.proto.staking_fee,
+            }
+        }
+        // this account owns the hotspot, but a maker paid for it
+        else {
+            no_difference(counterparty)
+        }
+    }
+}
+
+#[async_trait]
+impl GetDifference for AssertLocationV1 {
+    async fn get_difference(
+        &self,
+        account: &Address,
+        _client: &Client,
+        _height: u64,
+    ) -> Difference {
+        let counterparty = Some(Address::from_vec(self.proto.gateway.clone()).to_string());
+        // This account is paying the staking fee for the assertion
+        if fee_payer(&self.proto.payer, &self.proto.owner) == account.as_vec().as_slice() {
+            Difference {
+                counterparty,
+                hnt: Hnt::from(0),
+                dc: Dc::from(0),
+                fee: self.proto.fee + self.proto.staking_fee,
+            }
+        } else {
+            no_difference(counterparty)
+        }
+    }
+}
+
+#[async_trait]
+impl GetDifference for TransferHotspotV1 {
+    async fn get_difference(
+        &self,
+        account: &Address,
+        _client: &Client,
+        _height: u64,
+    ) -> Difference {
+        // This account is buying the hotspot and pays the fee
+        if self.proto.buyer == *account.as_vec() {
+            Difference {
+                counterparty: Some(Address::from_vec(self.proto.seller.clone()).to_string()),
+                hnt: Hnt::from(-(self.proto.amount_to_seller as isize)),
+                dc: Dc::from(0),
+                fee: self.proto.fee,
+            }
+        }
+        // this account is selling the hotspot
+        else {
+            Difference {
+                counterparty: Some(Address::from_vec(self.proto.buyer.clone()).to_string()),
+                hnt: Hnt::from(self.proto.amount_to_seller),
+                dc: Dc::from(0),
+                fee: 0,
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl GetDifference for StateChannelOpenV1 {
+    async fn get_difference(
+        &self,
+        account: &Address,
+        _client: &Client,
+        _height: u64,
+    ) -> Difference {
+        let counterparty = Some(format!("OUI {}", self.proto.oui));
+        // This account is escrowing DC in the state channel
+        if self.proto.owner == *account.as_vec() {
+            Difference {
+                counterparty,
+                hnt: Hnt::from(0),
+                dc: Dc::from(-(self.proto.amount as isize)),
+                fee: self.proto.fee,
+            }
+        } else {
+            no_difference(counterparty)
+        }
+    }
+}
+
+#[async_trait]
+impl GetDifference for OuiV1 {
+    async fn get_difference(
+        &self,
+        account: &Address,
+        _client: &Client,
+        _height: u64,
+    ) -> Difference {
+        let counterparty = Some(format!("OUI {}", self.proto.oui));
+        // This account is paying the staking fee for the OUI
+        if fee_payer(&self.proto.payer, &self.proto.owner) == account.as_vec().as_slice() {
+            Difference {
+                counterparty,
+                hnt: Hnt::from(0),
+                dc: Dc::from(0),
+                fee: self.proto.fee + self.proto.staking_fee,
+            }
+        } else {
+            no_difference(counterparty)
+        }
+    }
 }
 
 #[async_trait]
-trait ToRowWithMetadata {
-    async fn to_row_with_metadata(
+impl GetDifference for RoutingV1 {
+    async fn get_difference(
+        &self,
+        account: &Address,
+        _client: &Client,
+        _height: u64,
+    ) -> Difference {
+        let counterparty = Some(format!("OUI {}", self.proto.oui));
+        // This account owns the OUI and pays for the update, including the
+        // staking fee when buying a subnet
+        if self.proto.owner == *account.as_vec() {
+            Difference {
+                counterparty,
+                hnt: Hnt::from(0),
+                dc: Dc::from(0),
+                fee: self.proto.fee + self.proto.staking_fee,
+            }
+        } else {
+            no_difference(counterparty)
+        }
+    }
+}
+
+#[async_trait]
+impl GetDifference for CreateHtlcV1 {
+    async fn get_difference(
+        &self,
+        account: &Address,
+        _client: &Client,
+        _height: u64,
+    ) -> Difference {
+        // This account is locking HNT in the HTLC
+        if self.proto.payer == *account.as_vec() {
+            Difference {
+                counterparty: Some(Address::from_vec(self.proto.payee.clone()).to_string()),
+                hnt: Hnt::from(-(self.proto.amount as isize)),
+                dc: Dc::from(0),
+                fee: self.proto.fee,
+            }
+        }
+        // this account can redeem the HTLC, but receives nothing until it does
+        else {
+            no_difference(Some(
+                Address::from_vec(self.proto.payer.clone()).to_string(),
+            ))
+        }
+    }
+}
+
+/// Amount locked in an HTLC by the transaction that created it. The HTLC
+/// address is only used by its create and redeem transactions, so paging
+/// stops at the first match.
+async fn htlc_amount(client: &Client, htlc: &Address) -> Option<u64> {
+    let transactions = accounts::transactions(client, htlc.as_str());
+    pin_mut!(transactions);
+    while let Some(txn) = transactions.next().await {
+        match txn.ok()?.data {
+            Data::CreateHtlcV1(create) if create.proto.address == *htlc.as_vec() => {
+                return Some(create.proto.amount)
+            }
+            _ => (),
+        }
+    }
+    None
+}
+
+/// Redeeming an HTLC that held `amount`, flagging the row when the amount
+/// could not be looked up rather than report nothing was received
+fn redeemed(htlc: &Address, amount: Option<u64>, fee: u64) -> Difference {
+    match amount {
+        Some(amount) => Difference {
+            counterparty: Some(htlc.to_string()),
+            hnt: Hnt::from(amount),
+            dc: Dc::from(0),
+            fee,
+        },
+        None => Difference {
+            counterparty: Some(format!("{} (unknown amount)", htlc)),
+            hnt: Hnt::from(0),
+            dc: Dc::from(0),
+            fee,
+        },
+    }
+}
+
+#[async_trait]
+impl GetDifference for RedeemHtlcV1 {
+    async fn get_difference(&self, account: &Address, client: &Client, _height: u64) -> Difference {
+        let htlc = Address::from_vec(self.proto.address.clone());
+        // This account is redeeming the HTLC
+        if self.proto.payee == *account.as_vec() {
+            // the redeem transaction does not carry the amount, so look it up
+            // in the transaction that created the HTLC
+            let amount = htlc_amount(client, &htlc).await;
+            redeemed(&htlc, amount, self.proto.fee)
+        } else {
+            no_difference(Some(htlc.to_string()))
+        }
+    }
+}
+
+/// Moving a gateway to an OUI, whose fee the gateway owner pays. Whether
+/// the account is the owner is unknown when the lookup failed.
+fn oui_updated(oui: u64, fee: u64, is_owner: Option<bool>) -> Difference {
+    let counterparty = Some(format!("OUI {}", oui));
+    match is_owner {
+        Some(true) => Difference {
+            counterparty,
+            hnt: Hnt::from(0),
+            dc: Dc::from(0),
+            fee,
+        },
+        Some(false) => no_difference(counterparty),
+        None => no_difference(Some(format!("OUI {} (unknown fee payer)", oui))),
+    }
+}
+
+#[async_trait]
+impl GetDifference for UpdateGatewayOuiV1 {
+    async fn get_difference(&self, account: &Address, client: &Client, _height: u64) -> Difference {
+        let gateway = Address::from_vec(self.proto.gateway.clone());
+        // The gateway owner pays the fee, but only signs the transaction, so
+        // look the owner up. This is the current owner, which differs from
+        // the one at the time if the hotspot was transferred since.
+        let is_owner = hotspots::get(client, gateway.as_str())
+            .await
+            .ok()
+            .map(|hotspot| hotspot.owner == account.as_str());
+        oui_updated(self.proto.oui, self.proto.fee, is_owner)
+    }
+}
+
+/// Whether a payment or hotspot transfer in a bundle involves the account;
+/// the bundle as a whole does, but not necessarily each of its parts
+fn bundled_involves(txn: &Txn, account: &Address) -> bool {
+    let account = account.as_vec();
+    match txn {
+        Txn::Payment(payment) => payment.payer == *account || payment.payee == *account,
+        Txn::PaymentV2(payment) => {
+            payment.payer == *account
+                || payment
+                    .payments
+                    .iter()
+                    .any(|payment| payment.payee == *account)
+        }
+        Txn::TransferHotspot(transfer) => transfer.buyer == *account || transfer.seller == *account,
+        _ => true,
+    }
+}
+
+/// The difference of a transaction inside a bundle, for the kinds that can
+/// move HNT or DC
+async fn bundled_difference(
+    txn: &Txn,
+    account: &Address,
+    client: &Client,
+    height: u64,
+) -> Option<Difference> {
+    if !bundled_involves(txn, account) {
+        return None;
+    }
+    Some(match txn {
+        Txn::Payment(proto) => {
+            let txn = PaymentV1 {
+                proto: proto.clone(),
+            };
+            txn.get_difference(account, client, height).await
+        }
+        Txn::PaymentV2(proto) => {
+            let txn = PaymentV2 {
+                proto: proto.clone(),
+            };
+            txn.get_difference(account, client, height).await
+        }
+        Txn::TokenBurn(proto) => {
+            let txn = TokenBurnV1 {
+                proto: proto.clone(),
+            };
+            txn.get_difference(account, client, height).await
+        }
+        Txn::TransferHotspot(proto) => {
+            let txn = TransferHotspotV1 {
+                proto: proto.clone(),
+            };
+            txn.get_difference(account, client, height).await
+        }
+        Txn::CreateHtlc(proto) => {
+            let txn = CreateHtlcV1 {
+                proto: proto.clone(),
+            };
+            txn.get_difference(account, client, height).await
+        }
+        Txn::AddGateway(proto) => {
+            let txn = AddGatewayV1 {
+                proto: proto.clone(),
+            };
+            txn.get_difference(account, client, height).await
+        }
+        Txn::AssertLocation(proto) => {
+            let txn = AssertLocationV1 {
+                proto: proto.clone(),
+            };
+            txn.get_difference(account, client, height).await
+        }
+        _ => return None,
+    })
+}
+
+#[async_trait]
+impl GetDifference for BundleV1 {
+    async fn get_difference(&self, account: &Address, client: &Client, height: u64) -> Difference {
+        let mut counterparties: Vec<String> = Vec::new();
+        let mut hnt = Hnt::from(0).get_decimal();
+        let mut dc = Dc::from(0).get_decimal();
+        let mut fee = 0;
+        for txn in self
+            .proto
+            .transactions
+            .iter()
+            .filter_map(|txn| txn.txn.as_ref())
+        {
+            if let Some(difference) = bundled_difference(txn, account, client, height).await {
+                if let Some(counterparty) = difference.counterparty {
+                    if !counterparties.contains(&counterparty) {
+                        counterparties.push(counterparty);
+                    }
+                }
+                hnt += difference.hnt.get_decimal();
+                dc += difference.dc.get_decimal();
+                fee += difference.fee;
+            }
+        }
+        let counterparty = match counterparties.len() {
+            0 => None,
+            1 => counterparties.pop(),
+            _ => Some("many_counterparties".to_string()),
+        };
+        Difference {
+            counterparty,
+            hnt: Hnt::new(hnt),
+            dc: Dc::new(dc),
+            fee,
+        }
+    }
+}
+
+#[async_trait]
+impl GetDifference for CoinbaseV1 {
+    async fn get_difference(
+        &self,
+        account: &Address,
+        _client: &Client,
+        _height: u64,
+    ) -> Difference {
+        let counterparty = Some("Genesis".to_string());
+        if self.proto.payee == *account.as_vec() {
+            Difference {
+                counterparty,
+                hnt: Hnt::from(self.proto.amount),
+                dc: Dc::from(0),
+                fee: 0,
+            }
+        } else {
+            no_difference(counterparty)
+        }
+    }
+}
+
+#[async_trait]
+impl GetDifference for DcCoinbaseV1 {
+    async fn get_difference(
+        &self,
+        account: &Address,
+        _client: &Client,
+        _height: u64,
+    ) -> Difference {
+        let counterparty = Some("Genesis".to_string());
+        if self.proto.payee == *account.as_vec() {
+            Difference {
+                counterparty,
+                hnt: Hnt::from(0),
+                dc: Dc::from(self.proto.amount),
+                fee: 0,
+            }
+        } else {
+            no_difference(counterparty)
+        }
+    }
+}
+
+#[async_trait]
+impl GetDifference for SecurityExchangeV1 {
+    async fn get_difference(
+        &self,
+        account: &Address,
+        _client: &Client,
+        _height: u64,
+    ) -> Difference {
+        // Security tokens are not tracked, but the sender pays the fee
+        if self.proto.payer == *account.as_vec() {
+            Difference {
+                counterparty: Some(Address::from_vec(self.proto.payee.clone()).to_string()),
+                hnt: Hnt::from(0),
+                dc: Dc::from(0),
+                fee: self.proto.fee,
+            }
+        } else {
+            no_difference(Some(
+                Address::from_vec(self.proto.payer.clone()).to_string(),
+            ))
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Metadata {
+    pub height: usize,
+    pub hash: String,
+    pub time: usize,
+}
+
+/// The effect of a transaction on the account, one per row of the report
+pub struct Entry {
+    pub label: String,
+    /// Whether the HNT received is income, i.e. a mining reward
+    pub income: bool,
+    pub metadata: Metadata,
+    pub difference: Difference,
+}
+
+#[async_trait]
+trait ToEntriesWithMetadata {
+    async fn to_entries_with_metadata(
         &self,
         account: &Address,
         client: &Client,
         metadata: Metadata,
-    ) -> Row;
+    ) -> Vec<Entry>;
 }
 
-macro_rules! to_row {
+macro_rules! to_entries {
     ($self:ident, $txn:ident, $account:ident, $client:ident) => {{
         let metadata = $self.get_metadata();
-        $txn.to_row_with_metadata($account, $client, metadata).await
+        $txn.to_entries_with_metadata($account, $client, metadata)
+            .await
     }};
 }
 
 #[async_trait]
-impl ToRow for Transaction {
-    async fn to_row(&self, account: &Address, client: &Client) -> Row {
+impl ToEntries for Transaction {
+    async fn to_entries(&self, account: &Address, client: &Client) -> Vec<Entry> {
         match &self.data {
-            Data::PaymentV1(payment) => to_row!(self, payment, account, client),
-            Data::PaymentV2(payment_v2) => to_row!(self, payment_v2, account, client),
-            Data::RewardsV1(reward) => to_row!(self, reward, account, client),
-            Data::RewardsV2(reward) => to_row!(self, reward, account, client),
-            Data::TokenBurnV1(burn) => to_row!(self, burn, account, client),
-            Data::AddGatewayV1(add_gateway) => to_row!(self, add_gateway, account, client),
+            Data::PaymentV1(payment) => to_entries!(self, payment, account, client),
+            Data::PaymentV2(payment_v2) => to_entries!(self, payment_v2, account, client),
+            Data::RewardsV1(reward) => to_entries!(self, reward, account, client),
+            Data::RewardsV2(reward) => to_entries!(self, reward, account, client),
+            Data::TokenBurnV1(burn) => to_entries!(self, burn, account, client),
+            Data::AddGatewayV1(add_gateway) => to_entries!(self, add_gateway, account, client),
             Data::AssertLocationV1(assert_location) => {
-                to_row!(self, assert_location, account, client)
+                to_entries!(self, assert_location, account, client)
             }
-            Data::CoinbaseV1(coinbase) => to_row!(self, coinbase, account, client),
-            Data::CreateHtlcV1(create_htlc) => to_row!(self, create_htlc, account, client),
-            Data::GenGatewayV1(gen_gateway) => to_row!(self, gen_gateway, account, client),
+            Data::CoinbaseV1(coinbase) => to_entries!(self, coinbase, account, client),
+            Data::CreateHtlcV1(create_htlc) => to_entries!(self, create_htlc, account, client),
+            Data::GenGatewayV1(gen_gateway) => to_entries!(self, gen_gateway, account, client),
             Data::ConsensusGroupV1(consensus_group) => {
-                to_row!(self, consensus_group, account, client)
+                to_entries!(self, consensus_group, account, client)
             }
-            Data::OuiV1(oui) => to_row!(self, oui, account, client),
+            Data::OuiV1(oui) => to_entries!(self, oui, account, client),
             Data::PocReceiptsV1(poc_receipts) => {
-                to_row!(self, poc_receipts, account, client)
+                to_entries!(self, poc_receipts, account, client)
             }
-            Data::PocRequestV1(poc_request) => to_row!(self, poc_request, account, client),
-            Data::RedeemHtlcV1(redeem_htlc) => to_row!(self, redeem_htlc, account, client),
+            Data::PocRequestV1(poc_request) => to_entries!(self, poc_request, account, client),
+            Data::RedeemHtlcV1(redeem_htlc) => to_entries!(self, redeem_htlc, account, client),
             Data::SecurityCoinbaseV1(security_coinbase) => {
-                to_row!(self, security_coinbase, account, client)
+                to_entries!(self, security_coinbase, account, client)
             }
-            Data::RoutingV1(routing) => to_row!(self, routing, account, client),
+            Data::RoutingV1(routing) => to_entries!(self, routing, account, client),
             Data::SecurityExchangeV1(security_exchange) => {
-                to_row!(self, security_exchange, account, client)
+                to_entries!(self, security_exchange, account, client)
             }
-            Data::VarsV1(vars) => to_row!(self, vars, account, client),
-            Data::DcCoinbaseV1(dc_coinbase) => to_row!(self, dc_coinbase, account, client),
+            Data::VarsV1(vars) => to_entries!(self, vars, account, client),
+            Data::DcCoinbaseV1(dc_coinbase) => to_entries!(self, dc_coinbase, account, client),
             Data::TokenBurnExchangeRateV1(token_burn_exchange_rate) => {
-                to_row!(self, token_burn_exchange_rate, account, client)
+                to_entries!(self, token_burn_exchange_rate, account, client)
             }
-            Data::BundleV1(bundle) => to_row!(self, bundle, account, client),
+            Data::BundleV1(bundle) => to_entries!(self, bundle, account, client),
 
             Data::StateChannelOpenV1(state_channel_open) => {
-                to_row!(self, state_channel_open, account, client)
+                to_entries!(self, state_channel_open, account, client)
             }
 
             Data::UpdateGatewayOuiV1(update_gateway_oui) => {
-                to_row!(self, update_gateway_oui, account, client)
+                to_entries!(self, update_gateway_oui, account, client)
             }
 
             Data::StateChannelCloseV1(state_channel_close) => {
-                to_row!(self, state_channel_close, account, client)
+                to_entries!(self, state_channel_close, account, client)
             }
             Data::PriceOracleV1(price_oracle) => {
-                to_row!(self, price_oracle, account, client)
+                to_entries!(self, price_oracle, account, client)
             }
 
             Data::GenPriceOracleV1(gen_price_oracle) => {
-                to_row!(self, gen_price_oracle, account, client)
+                to_entries!(self, gen_price_oracle, account, client)
             }
 
             Data::TransferHotspotV1(transfer_hotspot) => {
-                to_row!(self, transfer_hotspot, account, client)
+                to_entries!(self, transfer_hotspot, account, client)
             }
         }
     }
@@ -307,7 +747,7 @@ impl GetMetadata for Transaction {
     }
 }
 
-fn utc_timestamp_from_epoch(time: usize) -> DateTime<Utc> {
+pub fn utc_timestamp_from_epoch(time: usize) -> DateTime<Utc> {
     DateTime::<Utc>::from_utc(NaiveDateTime::from_timestamp(time as i64, 0), Utc)
 }
 
@@ -319,94 +759,378 @@ fn get_common_rows(metadata: &Metadata) -> (Cell, Cell, Cell) {
     )
 }
 
-macro_rules! into_row {
+pub fn entry_to_row(entry: &Entry, valuation: &Valuation) -> Row {
+    let difference = &entry.difference;
+    // use metadata to generate the first few rows that are common
+    let common = get_common_rows(&entry.metadata);
+
+    // extract counterparty for row if there is one
+    let counterparty = if let Some(counterparty) = &difference.counterparty {
+        counterparty.as_str()
+    } else {
+        "NA"
+    };
+
+    Row::new(vec![
+        Cell::new(format!("{: <25}", entry.label).as_str()),
+        common.0,
+        common.1,
+        common.2,
+        Cell::new(&counterparty),
+        Cell::new(format!("{}", &difference.hnt.to_string()).as_str()),
+        Cell::new(format!("{}", (&difference.dc.to_string())).as_str()),
+        Cell::new(format!("{}", (&difference.fee.to_string())).as_str()),
+        Cell::new(&usd(valuation.hnt)),
+        Cell::new(&usd(Some(valuation.fee))),
+        Cell::new(&usd(valuation.cost_basis)),
+    ])
+}
+
+macro_rules! into_entry {
     ($Txn:ident, $Label:expr) => {
         #[async_trait]
-        impl ToRowWithMetadata for $Txn {
-            async fn to_row_with_metadata(
+        impl ToEntriesWithMetadata for $Txn {
+            async fn to_entries_with_metadata(
                 &self,
                 account: &Address,
                 client: &Client,
                 metadata: Metadata,
-            ) -> Row {
-                // use metadata to generate the first few rows that are common
-                let common = get_common_rows(&metadata);
+            ) -> Vec<Entry> {
                 // calculate the effect on the account
                 let difference = self
                     .get_difference(account, client, metadata.height as u64)
                     .await;
+                vec![Entry {
+                    label: $Label.to_string(),
+                    income: false,
+                    metadata,
+                    difference,
+                }]
+            }
+        }
+    };
+}
 
-                // extract counterparty for row if there is one
-                let counterparty = if let Some(counterparty) = &difference.counterparty {
-                    counterparty.as_str()
-                } else {
-                    "NA"
-                };
-
-                Row::new(vec![
-                    Cell::new(format!("{: <25}", $Label).as_str()),
-                    common.0,
-                    common.1,
-                    common.2,
-                    Cell::new(&counterparty),
-                    Cell::new(format!("{}", &difference.hnt.to_string()).as_str()),
-                    Cell::new(format!("{}", (&difference.dc.to_string())).as_str()),
-                    Cell::new(format!("{}", (&difference.fee.to_string())).as_str()),
-                ])
+// one entry per reward type and gateway, counting only the rewards paid to
+// the account
+macro_rules! rewards_into_entries {
+    ($Txn:ident, $Label:expr) => {
+        #[async_trait]
+        impl ToEntriesWithMetadata for $Txn {
+            async fn to_entries_with_metadata(
+                &self,
+                account: &Address,
+                _client: &Client,
+                metadata: Metadata,
+            ) -> Vec<Entry> {
+                let mut totals: BTreeMap<(i32, Vec<u8>), u64> = BTreeMap::new();
+                for reward in &self.proto.rewards {
+                    if reward.account == *account.as_vec() {
+                        *totals
+                            .entry((reward.r#type, reward.gateway.clone()))
+                            .or_default() += reward.amount;
+                    }
+                }
+                totals
+                    .into_iter()
+                    .map(|((reward_type, gateway), amount)| {
+                        let counterparty = if gateway.is_empty() {
+                            "Rewards".to_string()
+                        } else {
+                            Address::from_vec(gateway).to_string()
+                        };
+                        Entry {
+                            label: format!("{} {}", $Label, reward_type_name(reward_type)),
+                            income: true,
+                            metadata: metadata.clone(),
+                            difference: Difference {
+                                counterparty: Some(counterparty),
+                                hnt: Hnt::from(amount),
+                                dc: Dc::from(0),
+                                fee: 0,
+                            },
+                        }
+                    })
+                    .collect()
             }
         }
     };
 }
 
-into_row!(AddGatewayV1, "AddGatewayV1");
-into_row!(AssertLocationV1, "AssertLocationV1");
-into_row!(CoinbaseV1, "CoinbaseV1");
-into_row!(CreateHtlcV1, "CreateHtlcV1");
-into_row!(GenGatewayV1, "GenGatewayV1");
-into_row!(ConsensusGroupV1, "ConsensusGroupV1");
-into_row!(OuiV1, "OuiV1");
-into_row!(PaymentV1, "PaymentV1");
-into_row!(PocReceiptsV1, "PocReceiptsV1");
-into_row!(PocRequestV1, "PocRequestV1");
-into_row!(RedeemHtlcV1, "RedeemHtlcV1");
-into_row!(SecurityCoinbaseV1, "SecurityCoinbaseV1");
-into_row!(RoutingV1, "RoutingV1");
-into_row!(SecurityExchangeV1, "SecurityExchangeV1");
-into_row!(VarsV1, "VarsV1");
-into_row!(RewardsV1, "RewardsV1");
-into_row!(RewardsV2, "RewardsV2");
-into_row!(TokenBurnV1, "TokenBurnV1");
-into_row!(DcCoinbaseV1, "DcCoinbaseV1");
-into_row!(TokenBurnExchangeRateV1, "TokenBurnExchangeRateV1");
-into_row!(StateChannelOpenV1, "StateChannelOpenV1");
-into_row!(UpdateGatewayOuiV1, "UpdateGatewayOuiV1");
-into_row!(StateChannelCloseV1, "StateChannelCloseV1");
-into_row!(PaymentV2, "PaymentV2");
-into_row!(PriceOracleV1, "PriceOracleV1");
-into_row!(GenPriceOracleV1, "GenPriceOracleV1");
-into_row!(BundleV1, "BundleV1");
-into_row!(TransferHotspotV1, "TransferHotspotV1");
-
-dummy_difference!(AddGatewayV1);
-dummy_difference!(AssertLocationV1);
-dummy_difference!(CoinbaseV1);
-dummy_difference!(CreateHtlcV1);
+into_entry!(AddGatewayV1, "AddGatewayV1");
+into_entry!(AssertLocationV1, "AssertLocationV1");
+into_entry!(CoinbaseV1, "CoinbaseV1");
+into_entry!(CreateHtlcV1, "CreateHtlcV1");
+into_entry!(GenGatewayV1, "GenGatewayV1");
+into_entry!(ConsensusGroupV1, "ConsensusGroupV1");
+into_entry!(OuiV1, "OuiV1");
+into_entry!(PaymentV1, "PaymentV1");
+into_entry!(PocReceiptsV1, "PocReceiptsV1");
+into_entry!(PocRequestV1, "PocRequestV1");
+into_entry!(RedeemHtlcV1, "RedeemHtlcV1");
+into_entry!(SecurityCoinbaseV1, "SecurityCoinbaseV1");
+into_entry!(RoutingV1, "RoutingV1");
+into_entry!(SecurityExchangeV1, "SecurityExchangeV1");
+into_entry!(VarsV1, "VarsV1");
+rewards_into_entries!(RewardsV1, "RewardsV1");
+rewards_into_entries!(RewardsV2, "RewardsV2");
+into_entry!(TokenBurnV1, "TokenBurnV1");
+into_entry!(DcCoinbaseV1, "DcCoinbaseV1");
+into_entry!(TokenBurnExchangeRateV1, "TokenBurnExchangeRateV1");
+into_entry!(StateChannelOpenV1, "StateChannelOpenV1");
+into_entry!(UpdateGatewayOuiV1, "UpdateGatewayOuiV1");
+into_entry!(StateChannelCloseV1, "StateChannelCloseV1");
+into_entry!(PaymentV2, "PaymentV2");
+into_entry!(PriceOracleV1, "PriceOracleV1");
+into_entry!(GenPriceOracleV1, "GenPriceOracleV1");
+into_entry!(BundleV1, "BundleV1");
+into_entry!(TransferHotspotV1, "TransferHotspotV1");
+
+// no account pays or receives HNT or DC, or pays a fee, in these
 dummy_difference!(GenGatewayV1);
 dummy_difference!(ConsensusGroupV1);
-dummy_difference!(OuiV1);
 dummy_difference!(PocReceiptsV1);
 dummy_difference!(PocRequestV1);
-dummy_difference!(RedeemHtlcV1);
 dummy_difference!(SecurityCoinbaseV1);
-dummy_difference!(RoutingV1);
-dummy_difference!(SecurityExchangeV1);
 dummy_difference!(VarsV1);
-dummy_difference!(DcCoinbaseV1);
 dummy_difference!(TokenBurnExchangeRateV1);
-dummy_difference!(StateChannelOpenV1);
-dummy_difference!(UpdateGatewayOuiV1);
 dummy_difference!(StateChannelCloseV1);
 dummy_difference!(PriceOracleV1);
 dummy_difference!(GenPriceOracleV1);
-dummy_difference!(BundleV1);
-dummy_difference!(TransferHotspotV1);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use helium_proto::{
+        BlockchainTxn, BlockchainTxnAddGatewayV1, BlockchainTxnBundleV1, BlockchainTxnPaymentV1,
+        BlockchainTxnPaymentV2, Payment,
+    };
+    use rust_decimal::Decimal;
+
+    fn address(byte: u8) -> Address {
+        Address::from_vec(vec![0, byte])
+    }
+
+    fn payment(payer: &Address, payee: &Address, amount: u64) -> Txn {
+        Txn::Payment(BlockchainTxnPaymentV1 {
+            payer: payer.as_vec().clone(),
+            payee: payee.as_vec().clone(),
+            amount,
+            fee: 10,
+            ..Default::default()
+        })
+    }
+
+    fn push_reward(
+        txn: &mut RewardsV2,
+        account: &Address,
+        gateway: &Address,
+        reward_type: i32,
+        amount: u64,
+    ) {
+        txn.proto.rewards.push(Default::default());
+        let reward = txn.proto.rewards.last_mut().unwrap();
+        reward.account = account.as_vec().clone();
+        reward.gateway = gateway.as_vec().clone();
+        reward.r#type = reward_type;
+        reward.amount = amount;
+    }
+
+    fn bundle(txns: Vec<Txn>) -> BundleV1 {
+        BundleV1 {
+            proto: BlockchainTxnBundleV1 {
+                transactions: txns
+                    .into_iter()
+                    .map(|txn| BlockchainTxn { txn: Some(txn) })
+                    .collect(),
+            },
+        }
+    }
+
+    fn add_gateway(owner: &Address, payer: &[u8]) -> AddGatewayV1 {
+        AddGatewayV1 {
+            proto: BlockchainTxnAddGatewayV1 {
+                owner: owner.as_vec().clone(),
+                gateway: address(9).as_vec().clone(),
+                payer: payer.to_vec(),
+                fee: 10,
+                staking_fee: 100,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn fee_is_paid_by_owner_without_payer() {
+        assert_eq!(fee_payer(&[], &[1]), &[1]);
+        assert_eq!(fee_payer(&[2], &[1]), &[2]);
+    }
+
+    #[tokio::test]
+    async fn add_gateway_fee_goes_to_payer() {
+        let client = Client::default();
+        let owner = address(1);
+        let maker = address(2);
+
+        let txn = add_gateway(&owner, &[]);
+        let difference = txn.get_difference(&owner, &client, 1).await;
+        assert_eq!(difference.fee, 110);
+
+        let txn = add_gateway(&owner, maker.as_vec());
+        assert_eq!(txn.get_difference(&owner, &client, 1).await.fee, 0);
+        assert_eq!(txn.get_difference(&maker, &client, 1).await.fee, 110);
+    }
+
+    #[tokio::test]
+    async fn bundle_sums_payments_of_the_account() {
+        let client = Client::default();
+        let (buyer, seller, other) = (address(1), address(2), address(3));
+        let txn = bundle(vec![
+            payment(&buyer, &seller, 300),
+            payment(&other, &buyer, 50),
+            // neither side is the buyer, so it is not counted
+            payment(&seller, &other, 7),
+        ]);
+
+        let difference = txn.get_difference(&buyer, &client, 1).await;
+        assert_eq!(
+            difference.hnt.get_decimal(),
+            Hnt::from(50).get_decimal() - Hnt::from(300).get_decimal()
+        );
+        assert_eq!(
+            difference.counterparty.as_deref(),
+            Some("many_counterparties")
+        );
+
+        let difference = txn.get_difference(&seller, &client, 1).await;
+        assert_eq!(
+            difference.hnt.get_decimal(),
+            Hnt::from(300).get_decimal() - Hnt::from(7).get_decimal()
+        );
+    }
+
+    #[test]
+    fn bundled_payment_v2_involves_any_payee() {
+        let (payer, payee, other) = (address(1), address(2), address(3));
+        let txn = Txn::PaymentV2(BlockchainTxnPaymentV2 {
+            payer: payer.as_vec().clone(),
+            payments: vec![Payment {
+                payee: payee.as_vec().clone(),
+                amount: 1,
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+        assert!(bundled_involves(&txn, &payer));
+        assert!(bundled_involves(&txn, &payee));
+        assert!(!bundled_involves(&txn, &other));
+    }
+
+    #[test]
+    fn unknown_htlc_amount_is_flagged() {
+        let htlc = address(4);
+        let difference = redeemed(&htlc, Some(5), 1);
+        assert_eq!(difference.hnt.get_decimal(), Hnt::from(5).get_decimal());
+        assert_eq!(difference.counterparty, Some(htlc.to_string()));
+
+        let difference = redeemed(&htlc, None, 1);
+        assert_eq!(difference.hnt.get_decimal(), Hnt::from(0).get_decimal());
+        assert_eq!(difference.fee, 1);
+        assert_eq!(
+            difference.counterparty,
+            Some(format!("{} (unknown amount)", htlc))
+        );
+    }
+
+    #[test]
+    fn oui_update_fee_is_paid_by_gateway_owner() {
+        assert_eq!(oui_updated(3, 40, Some(true)).fee, 40);
+        assert_eq!(oui_updated(3, 40, Some(false)).fee, 0);
+        let unknown = oui_updated(3, 40, None);
+        assert_eq!(unknown.fee, 0);
+        assert_eq!(
+            unknown.counterparty.as_deref(),
+            Some("OUI 3 (unknown fee payer)")
+        );
+    }
+
+    #[tokio::test]
+    async fn rewards_are_split_by_type_and_gateway() {
+        let client = Client::default();
+        let (account, other) = (address(1), address(2));
+        let (first, second) = (address(8), address(9));
+        let mut txn = RewardsV2 {
+            proto: Default::default(),
+        };
+        push_reward(&mut txn, &account, &first, 2, 10);
+        push_reward(&mut txn, &account, &first, 2, 5);
+        push_reward(&mut txn, &account, &first, 4, 3);
+        push_reward(&mut txn, &account, &second, 4, 7);
+        push_reward(&mut txn, &account, &Address::default(), 5, 1);
+        push_reward(&mut txn, &other, &first, 2, 100);
+        push_reward(&mut txn, &other, &second, 5, 100);
+        let metadata = Metadata {
+            height: 1,
+            hash: "hash".to_string(),
+            time: 0,
+        };
+
+        let entries = txn
+            .to_entries_with_metadata(&account, &client, metadata)
+            .await;
+        let rows: Vec<(String, Option<String>, Decimal)> = entries
+            .iter()
+            .map(|entry| {
+                assert!(entry.income);
+                (
+                    entry.label.clone(),
+                    entry.difference.counterparty.clone(),
+                    entry.difference.hnt.get_decimal(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (
+                    "RewardsV2 PocChallengees".to_string(),
+                    Some(first.to_string()),
+                    Hnt::from(15).get_decimal()
+                ),
+                (
+                    "RewardsV2 PocWitnesses".to_string(),
+                    Some(first.to_string()),
+                    Hnt::from(3).get_decimal()
+                ),
+                (
+                    "RewardsV2 PocWitnesses".to_string(),
+                    Some(second.to_string()),
+                    Hnt::from(7).get_decimal()
+                ),
+                (
+                    "RewardsV2 Consensus".to_string(),
+                    Some("Rewards".to_string()),
+                    Hnt::from(1).get_decimal()
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn rewards_of_other_accounts_are_dropped() {
+        let client = Client::default();
+        let mut txn = RewardsV2 {
+            proto: Default::default(),
+        };
+        push_reward(&mut txn, &address(2), &address(8), 2, 100);
+        let metadata = Metadata {
+            height: 1,
+            hash: "hash".to_string(),
+            time: 0,
+        };
+        let entries = txn
+            .to_entries_with_metadata(&address(1), &client, metadata)
+            .await;
+        assert!(entries.is_empty());
+    }
+}
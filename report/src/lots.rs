@@ -0,0 +1,449 @@
+use super::{usd, utc_timestamp_from_epoch, Entry};
+use chrono::{Datelike, Duration};
+use prettytable::{row, Cell, Row, Table};
+use rust_decimal::Decimal;
+use std::{collections::BTreeMap, str::FromStr};
+
+/// Holdings longer than this are long-term for tax purposes
+const LONG_TERM_DAYS: i64 = 365;
+
+/// Which lots are disposed of first
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LotMethod {
+    /// First in, first out
+    Fifo,
+    /// Last in, first out
+    Lifo,
+    /// Highest cost first
+    Hifo,
+}
+
+impl FromStr for LotMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<LotMethod, String> {
+        match s.to_lowercase().as_str() {
+            "fifo" => Ok(LotMethod::Fifo),
+            "lifo" => Ok(LotMethod::Lifo),
+            "hifo" => Ok(LotMethod::Hifo),
+            _ => Err(format!(
+                "unknown lot method {:?}, use fifo, lifo or hifo",
+                s
+            )),
+        }
+    }
+}
+
+/// HNT acquired in one transaction and not disposed of yet
+struct Lot {
+    time: usize,
+    hnt: Decimal,
+    /// USD per HNT when acquired, if there was a price
+    price: Option<Decimal>,
+}
+
+/// Part of a disposal matched to one lot. USD amounts are unknown when
+/// there was no price at the disposal or the acquisition.
+struct Disposal {
+    hash: String,
+    time: usize,
+    /// When the lot was acquired; unknown if the history holds no lot to
+    /// match, in which case the cost is zero
+    acquired: Option<usize>,
+    hnt: Decimal,
+    proceeds: Option<Decimal>,
+    cost: Option<Decimal>,
+}
+
+impl Disposal {
+    /// "long" or "short" term, or "unknown" when there was no lot to match
+    fn term(&self) -> &'static str {
+        match self.acquired {
+            Some(acquired) => {
+                let held = utc_timestamp_from_epoch(self.time) - utc_timestamp_from_epoch(acquired);
+                if held > Duration::days(LONG_TERM_DAYS) {
+                    "long"
+                } else {
+                    "short"
+                }
+            }
+            None => "unknown",
+        }
+    }
+
+    fn gain(&self) -> Option<Decimal> {
+        self.proceeds
+            .and_then(|proceeds| self.cost.map(|cost| proceeds - cost))
+    }
+}
+
+#[derive(Default)]
+struct Income {
+    hnt: Decimal,
+    usd: Decimal,
+    /// Whether some of the HNT had no price, leaving the USD total unknown
+    unpriced: bool,
+}
+
+impl Income {
+    fn usd(&self) -> Option<Decimal> {
+        Some(self.usd).filter(|_| !self.unpriced)
+    }
+}
+
+/// Tracks tax lots over the entries of one account. HNT received becomes a
+/// lot at its value on receipt, with rewards also counted as income; HNT
+/// sent or burnt is a disposal at its value at the time, matched against
+/// lots by the chosen method.
+pub struct TaxLots {
+    method: LotMethod,
+    lots: Vec<Lot>,
+    disposals: Vec<Disposal>,
+    income: BTreeMap<i32, Income>,
+}
+
+impl TaxLots {
+    pub fn new(method: LotMethod) -> TaxLots {
+        TaxLots {
+            method,
+            lots: Vec::new(),
+            disposals: Vec::new(),
+            income: BTreeMap::new(),
+        }
+    }
+
+    /// Applies an entry valued at `price` USD per HNT. Entries have to be
+    /// applied in chronological order, including the ones that are not
+    /// `reported`: those only acquire and dispose of lots, without adding
+    /// to the gains and income tables.
+    pub fn apply(&mut self, entry: &Entry, price: Option<Decimal>, reported: bool) {
+        let zero = Decimal::new(0, 0);
+        let time = entry.metadata.time;
+        let hnt = entry.difference.hnt.get_decimal();
+        if hnt > zero {
+            if entry.income && reported {
+                let year = utc_timestamp_from_epoch(time).year();
+                let income = self.income.entry(year).or_default();
+                income.hnt += hnt;
+                match price {
+                    Some(price) => income.usd += hnt * price,
+                    None => income.unpriced = true,
+                }
+            }
+            self.lots.push(Lot { time, hnt, price });
+        } else if hnt < zero {
+            let mut disposals = Vec::new();
+            let mut remaining = -hnt;
+            while remaining > zero {
+                let index = match self.next_lot() {
+                    Some(index) => index,
+                    None => break,
+                };
+                let lot = &mut self.lots[index];
+                let matched = remaining.min(lot.hnt);
+                disposals.push(Disposal {
+                    hash: entry.metadata.hash.clone(),
+                    time,
+                    acquired: Some(lot.time),
+                    hnt: matched,
+                    proceeds: price.map(|price| matched * price),
+                    cost: lot.price.map(|price| matched * price),
+                });
+                lot.hnt -= matched;
+                remaining -= matched;
+                if lot.hnt <= zero {
+                    self.lots.remove(index);
+                }
+            }
+            if remaining > zero {
+                disposals.push(Disposal {
+                    hash: entry.metadata.hash.clone(),
+                    time,
+                    acquired: None,
+                    hnt: remaining,
+                    proceeds: price.map(|price| remaining * price),
+                    cost: Some(zero),
+                });
+            }
+            if reported {
+                self.disposals.extend(disposals);
+            }
+        }
+    }
+
+    /// USD cost of the HNT still held, unknown if a lot had no price
+    pub fn cost_basis(&self) -> Option<Decimal> {
+        self.lots
+            .iter()
+            .map(|lot| lot.price.map(|price| lot.hnt * price))
+            .sum()
+    }
+
+    fn next_lot(&self) -> Option<usize> {
+        if self.lots.is_empty() {
+            return None;
+        }
+        Some(match self.method {
+            LotMethod::Fifo => 0,
+            LotMethod::Lifo => self.lots.len() - 1,
+            // lots without a price sort lowest
+            LotMethod::Hifo => {
+                let mut highest = 0;
+                for (index, lot) in self.lots.iter().enumerate() {
+                    if lot.price > self.lots[highest].price {
+                        highest = index;
+                    }
+                }
+                highest
+            }
+        })
+    }
+
+    /// One row per disposal and lot it was matched to
+    pub fn gains_table(&self) -> Table {
+        let mut table = Table::new();
+        table.add_row(row![
+            "Date Disposed",
+            "Date Acquired",
+            "Hash",
+            "HNT",
+            "Proceeds USD",
+            "Cost USD",
+            "Gain USD",
+            "Term",
+        ]);
+        for disposal in &self.disposals {
+            let disposed = utc_timestamp_from_epoch(disposal.time);
+            let acquired = match disposal.acquired {
+                Some(acquired) => utc_timestamp_from_epoch(acquired).to_rfc3339(),
+                None => "NA".to_string(),
+            };
+            table.add_row(Row::new(vec![
+                Cell::new(&disposed.to_rfc3339()),
+                Cell::new(&acquired),
+                Cell::new(&disposal.hash),
+                Cell::new(&disposal.hnt.to_string()),
+                Cell::new(&usd(disposal.proceeds)),
+                Cell::new(&usd(disposal.cost)),
+                Cell::new(&usd(disposal.gain())),
+                Cell::new(disposal.term()),
+            ]));
+        }
+        table
+    }
+
+    /// Income from rewards and realized gains, per calendar year
+    pub fn income_table(&self, address: &str) -> Table {
+        let zero = Decimal::new(0, 0);
+        let mut gains: BTreeMap<i32, Option<Decimal>> = BTreeMap::new();
+        for disposal in &self.disposals {
+            let year = utc_timestamp_from_epoch(disposal.time).year();
+            let total = gains.entry(year).or_insert(Some(zero));
+            *total = total.and_then(|total| disposal.gain().map(|gain| total + gain));
+        }
+        let mut years: Vec<i32> = self.income.keys().chain(gains.keys()).cloned().collect();
+        years.sort_unstable();
+        years.dedup();
+
+        let mut table = Table::new();
+        table.add_row(row![
+            "Address",
+            "Year",
+            "Income HNT",
+            "Income USD",
+            "Realized Gain USD",
+        ]);
+        for year in years {
+            let income = self.income.get(&year);
+            table.add_row(Row::new(vec![
+                Cell::new(address),
+                Cell::new(&year.to_string()),
+                Cell::new(&income.map(|i| i.hnt).unwrap_or_default().to_string()),
+                Cell::new(&usd(income.map_or(Some(zero), Income::usd))),
+                Cell::new(&usd(gains.get(&year).cloned().unwrap_or(Some(zero)))),
+            ]));
+        }
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Difference, Metadata};
+    use helium_api::{Dc, Hnt};
+
+    const DAY: usize = 24 * 60 * 60;
+    /// 2020-09-13, after the price oracles started
+    const START: usize = 1_600_000_000;
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    fn entry(day: usize, hnt: &str, income: bool) -> Entry {
+        Entry {
+            label: "test".to_string(),
+            income,
+            metadata: Metadata {
+                height: day,
+                hash: format!("hash{}", day),
+                time: START + day * DAY,
+            },
+            difference: Difference {
+                counterparty: None,
+                hnt: Hnt::new(dec(hnt)),
+                dc: Dc::from(0),
+                fee: 0,
+            },
+        }
+    }
+
+    /// Buys 10 HNT at $1, 10 at $3 and 10 at $2, then sells 15 at $5
+    fn sell_after_three_buys(method: LotMethod) -> TaxLots {
+        let mut lots = TaxLots::new(method);
+        lots.apply(&entry(0, "10", false), Some(dec("1")), true);
+        lots.apply(&entry(1, "10", false), Some(dec("3")), true);
+        lots.apply(&entry(2, "10", false), Some(dec("2")), true);
+        lots.apply(&entry(3, "-15", false), Some(dec("5")), true);
+        lots
+    }
+
+    /// (day acquired, HNT, cost) of each disposal
+    fn matched(lots: &TaxLots) -> Vec<(Option<usize>, Decimal, Option<Decimal>)> {
+        lots.disposals
+            .iter()
+            .map(|disposal| {
+                (
+                    disposal.acquired.map(|time| (time - START) / DAY),
+                    disposal.hnt,
+                    disposal.cost,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fifo_disposes_oldest_lots_first() {
+        let lots = sell_after_three_buys(LotMethod::Fifo);
+        assert_eq!(
+            matched(&lots),
+            vec![
+                (Some(0), dec("10"), Some(dec("10"))),
+                (Some(1), dec("5"), Some(dec("15"))),
+            ]
+        );
+        assert_eq!(lots.cost_basis(), Some(dec("35")));
+    }
+
+    #[test]
+    fn lifo_disposes_newest_lots_first() {
+        let lots = sell_after_three_buys(LotMethod::Lifo);
+        assert_eq!(
+            matched(&lots),
+            vec![
+                (Some(2), dec("10"), Some(dec("20"))),
+                (Some(1), dec("5"), Some(dec("15"))),
+            ]
+        );
+        assert_eq!(lots.cost_basis(), Some(dec("25")));
+    }
+
+    #[test]
+    fn hifo_disposes_costliest_lots_first() {
+        let lots = sell_after_three_buys(LotMethod::Hifo);
+        assert_eq!(
+            matched(&lots),
+            vec![
+                (Some(1), dec("10"), Some(dec("30"))),
+                (Some(2), dec("5"), Some(dec("10"))),
+            ]
+        );
+        assert_eq!(lots.cost_basis(), Some(dec("20")));
+    }
+
+    #[test]
+    fn partly_disposed_lot_is_kept() {
+        let mut lots = TaxLots::new(LotMethod::Fifo);
+        lots.apply(&entry(0, "10", false), Some(dec("1")), true);
+        lots.apply(&entry(1, "-4", false), Some(dec("2")), true);
+        lots.apply(&entry(2, "-4", false), Some(dec("2")), true);
+        assert_eq!(lots.lots.len(), 1);
+        assert_eq!(lots.lots[0].hnt, dec("2"));
+        assert_eq!(lots.cost_basis(), Some(dec("2")));
+        assert_eq!(lots.disposals[1].gain(), Some(dec("4")));
+    }
+
+    #[test]
+    fn unmatched_disposal_has_no_cost() {
+        let mut lots = TaxLots::new(LotMethod::Fifo);
+        lots.apply(&entry(0, "1", false), Some(dec("1")), true);
+        lots.apply(&entry(1, "-3", false), Some(dec("2")), true);
+        assert_eq!(
+            matched(&lots),
+            vec![
+                (Some(0), dec("1"), Some(dec("1"))),
+                (None, dec("2"), Some(dec("0"))),
+            ]
+        );
+        assert_eq!(lots.disposals[1].term(), "unknown");
+        assert_eq!(lots.disposals[1].gain(), Some(dec("4")));
+        assert!(lots.lots.is_empty());
+    }
+
+    #[test]
+    fn term_splits_at_a_year() {
+        let mut lots = TaxLots::new(LotMethod::Fifo);
+        lots.apply(&entry(0, "2", false), Some(dec("1")), true);
+        lots.apply(&entry(365, "-1", false), Some(dec("1")), true);
+        lots.apply(&entry(366, "-1", false), Some(dec("1")), true);
+        assert_eq!(lots.disposals[0].term(), "short");
+        assert_eq!(lots.disposals[1].term(), "long");
+    }
+
+    #[test]
+    fn rewards_are_income_of_their_year() {
+        let mut lots = TaxLots::new(LotMethod::Fifo);
+        lots.apply(&entry(0, "2", true), Some(dec("1.5")), true);
+        lots.apply(&entry(1, "3", false), Some(dec("1.5")), true);
+        lots.apply(&entry(200, "1", true), Some(dec("2")), true);
+        let income = &lots.income[&2020];
+        assert_eq!(income.hnt, dec("2"));
+        assert_eq!(income.usd(), Some(dec("3")));
+        assert_eq!(lots.income[&2021].usd(), Some(dec("2")));
+    }
+
+    #[test]
+    fn missing_price_leaves_usd_unknown() {
+        let mut lots = TaxLots::new(LotMethod::Fifo);
+        lots.apply(&entry(0, "1", true), None, true);
+        lots.apply(&entry(1, "1", false), Some(dec("1")), true);
+        assert_eq!(lots.cost_basis(), None);
+        assert_eq!(lots.income[&2020].usd(), None);
+
+        lots.apply(&entry(2, "-2", false), Some(dec("2")), true);
+        assert_eq!(lots.disposals[0].cost, None);
+        assert_eq!(lots.disposals[0].gain(), None);
+        assert_eq!(lots.disposals[1].gain(), Some(dec("1")));
+        assert_eq!(lots.cost_basis(), Some(dec("0")));
+    }
+
+    #[test]
+    fn unreported_entries_still_move_lots() {
+        let mut lots = TaxLots::new(LotMethod::Fifo);
+        lots.apply(&entry(0, "10", true), Some(dec("1")), false);
+        lots.apply(&entry(1, "-4", false), Some(dec("2")), false);
+        assert!(lots.income.is_empty());
+        assert!(lots.disposals.is_empty());
+
+        lots.apply(&entry(2, "-6", false), Some(dec("3")), true);
+        assert_eq!(matched(&lots), vec![(Some(0), dec("6"), Some(dec("6")))]);
+        assert_eq!(lots.cost_basis(), Some(dec("0")));
+    }
+
+    #[test]
+    fn lot_method_parses_any_case() {
+        assert_eq!("HIFO".parse::<LotMethod>(), Ok(LotMethod::Hifo));
+        assert!("average".parse::<LotMethod>().is_err());
+    }
+}
@@ -1,21 +1,76 @@
-use helium_api::{accounts, transactions::*, Client, IntoVec};
+use futures::{pin_mut, Stream, StreamExt};
+use helium_api::{accounts, transactions::*, Client};
 use prettytable::{cell, row, Table};
+use rust_decimal::Decimal;
 use std::fs::File;
 use structopt::StructOpt;
 
 mod accounting;
+mod lots;
+mod range;
 mod types;
+mod valuation;
 
 use accounting::*;
+use lots::*;
+use range::*;
 use types::*;
+use valuation::*;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 
 #[derive(Debug, StructOpt)]
 pub struct Cli {
     address: String,
     #[structopt(long)]
     all: bool,
+    /// Track tax lots, disposed of in this order: fifo, lifo or hifo. Writes
+    /// the gains and income reports and fills the cost basis column with
+    /// the cost of the lots left after each entry. Needs `--all`, as lots
+    /// are only disposed of by payments and burns, and fetches the history
+    /// from before the range too, as its lots are still held.
+    #[structopt(long, requires = "all")]
+    lots: Option<LotMethod>,
+    /// Only report transactions on or after this date (YYYY-MM-DD, UTC)
+    #[structopt(long)]
+    since: Option<NaiveDate>,
+    /// Only report transactions on or before this date (YYYY-MM-DD, UTC)
+    #[structopt(long)]
+    until: Option<NaiveDate>,
+    /// Only report transactions at or above this block height
+    #[structopt(long)]
+    min_block: Option<usize>,
+    /// Only report transactions at or below this block height
+    #[structopt(long)]
+    max_block: Option<usize>,
+    /// Only report transactions in this calendar year, like
+    /// `--since YEAR-01-01 --until YEAR-12-31`
+    #[structopt(long, conflicts_with_all = &["since", "until"])]
+    tax_year: Option<i32>,
+}
+
+/// Collects the transactions up to the end of the range, and stops fetching
+/// pages once they are older than the range unless `full_history` is set
+async fn fetch<S, E>(
+    transactions: S,
+    range: &Range,
+    full_history: bool,
+) -> Result<Vec<Transaction>, E>
+where
+    S: Stream<Item = Result<Transaction, E>>,
+{
+    pin_mut!(transactions);
+    let mut fetched = Vec::new();
+    while let Some(txn) = transactions.next().await {
+        let txn = txn?;
+        if !full_history && range.is_before(txn.height, txn.time) {
+            break;
+        }
+        if !range.is_after(txn.height, txn.time) {
+            fetched.push(txn);
+        }
+    }
+    Ok(fetched)
 }
 
 #[tokio::main]
@@ -23,12 +78,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::from_args();
 
     let client = Client::default();
+    let range = Range::from_cli(&cli).unwrap_or_else(|e| {
+        structopt::clap::Error::with_description(&e, structopt::clap::ErrorKind::InvalidValue)
+            .exit()
+    });
+    // the lots held at the start of the range may go back to the first
+    // transaction
+    let full_history = cli.lots.is_some();
     let transactions = if cli.all {
-        accounts::transactions(&client, &cli.address)
-            .into_vec()
-            .await?
+        let transactions = accounts::transactions(&client, &cli.address);
+        fetch(transactions, &range, full_history).await?
     } else {
-        accounts::rewards(&client, &cli.address).into_vec().await?
+        // lots need --all, so the rewards history can stop at the range
+        fetch(accounts::rewards(&client, &cli.address), &range, false).await?
     };
 
     let mut table = Table::new();
@@ -41,27 +103,54 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         "HNT",
         "DC",
         "Fee",
+        "HNT-USD",
+        "Fee-USD",
+        "Cost Basis USD",
     ]);
 
+    let account = Address::from_str(&cli.address)?;
+    let mut entries = Vec::new();
     for txn in transactions {
-        if cli.all {
-            table.add_row(txn.to_row(&Address::from_str(&cli.address)?, &client).await);
+        let rewards = matches!(&txn.data, Data::RewardsV1(_) | Data::RewardsV2(_));
+        if cli.all || rewards {
+            entries.extend(txn.to_entries(&account, &client).await);
+        }
+    }
+    // the running cost basis has to be worked out oldest first
+    entries.sort_by_key(|entry| entry.metadata.height);
+
+    let mut prices = PriceCache::default();
+    let mut tax_lots = cli.lots.map(TaxLots::new);
+    for entry in &entries {
+        let reported = range.contains(&entry.metadata);
+        // entries before the range only need a price for the cost of the
+        // lots they acquire
+        let acquired =
+            tax_lots.is_some() && entry.difference.hnt.get_decimal() > Decimal::new(0, 0);
+        let price = if reported || acquired {
+            prices.at_block(&client, entry.metadata.height as u64).await
         } else {
-            if let Data::RewardsV1(_) = &txn.data {
-                table.add_row(txn.to_row(&Address::from_str(&cli.address)?, &client).await);
-            }
-            if let Data::RewardsV2(_) = &txn.data {
-                table.add_row(txn.to_row(&Address::from_str(&cli.address)?, &client).await);
-            }
+            None
+        };
+        if let Some(tax_lots) = &mut tax_lots {
+            tax_lots.apply(entry, price, reported);
+        }
+        if reported {
+            let cost_basis = tax_lots.as_ref().and_then(TaxLots::cost_basis);
+            let valuation = Valuation::new(&entry.difference, price, cost_basis);
+            table.add_row(entry_to_row(entry, &valuation));
         }
     }
 
     let time: DateTime<Utc> = Utc::now();
-    let out = File::create(format!(
-        "{}_{}.csv",
-        cli.address,
-        time.format("%Y-%m-%d_%H-%M-%S").to_string()
-    ))?;
+    let time = time.format("%Y-%m-%d_%H-%M-%S").to_string();
+    let out = File::create(format!("{}_{}.csv", cli.address, time))?;
     table.to_csv(out)?;
+    if let Some(tax_lots) = tax_lots {
+        let out = File::create(format!("{}_gains_{}.csv", cli.address, time))?;
+        tax_lots.gains_table().to_csv(out)?;
+        let out = File::create(format!("{}_income_{}.csv", cli.address, time))?;
+        tax_lots.income_table(&cli.address).to_csv(out)?;
+    }
     Ok(())
 }
@@ -0,0 +1,128 @@
+use super::{Cli, Metadata};
+use chrono::NaiveDate;
+
+/// The transactions to report on, by block height and time
+#[derive(Default)]
+pub struct Range {
+    min_block: Option<usize>,
+    max_block: Option<usize>,
+    min_time: Option<i64>,
+    max_time: Option<i64>,
+}
+
+fn start_of(date: NaiveDate) -> i64 {
+    date.and_hms(0, 0, 0).timestamp()
+}
+
+fn end_of(date: NaiveDate) -> i64 {
+    date.and_hms(23, 59, 59).timestamp()
+}
+
+impl Range {
+    pub fn from_cli(cli: &Cli) -> Result<Range, String> {
+        let (since, until) = match cli.tax_year {
+            Some(year) => {
+                let date = |month, day| {
+                    NaiveDate::from_ymd_opt(year, month, day)
+                        .ok_or_else(|| format!("tax year {} is out of range", year))
+                };
+                (Some(date(1, 1)?), Some(date(12, 31)?))
+            }
+            None => (cli.since, cli.until),
+        };
+        Ok(Range {
+            min_block: cli.min_block,
+            max_block: cli.max_block,
+            min_time: since.map(start_of),
+            max_time: until.map(end_of),
+        })
+    }
+
+    pub fn contains(&self, metadata: &Metadata) -> bool {
+        !self.is_before(metadata.height, metadata.time)
+            && !self.is_after(metadata.height, metadata.time)
+    }
+
+    /// Whether a transaction at `height` and `time` is older than the range.
+    /// Transactions are listed newest first, so all the ones after it are
+    /// too.
+    pub fn is_before(&self, height: usize, time: usize) -> bool {
+        matches!(self.min_block, Some(min) if height < min)
+            || matches!(self.min_time, Some(min) if (time as i64) < min)
+    }
+
+    /// Whether a transaction at `height` and `time` is newer than the range
+    pub fn is_after(&self, height: usize, time: usize) -> bool {
+        matches!(self.max_block, Some(max) if height > max)
+            || matches!(self.max_time, Some(max) if time as i64 > max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use structopt::StructOpt;
+
+    fn range(args: &[&str]) -> Result<Range, String> {
+        let cli = Cli::from_iter_safe(["report", "address"].iter().chain(args)).unwrap();
+        Range::from_cli(&cli)
+    }
+
+    fn at(height: usize, date: &str) -> Metadata {
+        Metadata {
+            height,
+            hash: String::new(),
+            time: NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .unwrap()
+                .and_hms(12, 0, 0)
+                .timestamp() as usize,
+        }
+    }
+
+    #[test]
+    fn tax_year_covers_the_calendar_year() {
+        let range = range(&["--tax-year", "2021"]).unwrap();
+        assert!(!range.contains(&at(1, "2020-12-31")));
+        assert!(range.contains(&at(1, "2021-01-01")));
+        assert!(range.contains(&at(1, "2021-12-31")));
+        assert!(!range.contains(&at(1, "2022-01-01")));
+    }
+
+    #[test]
+    fn blocks_are_inclusive() {
+        let range = range(&["--min-block", "10", "--max-block", "20"]).unwrap();
+        assert!(!range.contains(&at(9, "2021-01-01")));
+        assert!(range.contains(&at(10, "2021-01-01")));
+        assert!(range.contains(&at(20, "2021-01-01")));
+        assert!(!range.contains(&at(21, "2021-01-01")));
+    }
+
+    #[test]
+    fn dates_before_1970_do_not_wrap() {
+        let range = range(&["--until", "1969-12-31"]).unwrap();
+        assert!(!range.contains(&at(1, "2021-01-01")));
+        let range = range(&["--since", "1969-01-01"]).unwrap();
+        assert!(range.contains(&at(1, "2021-01-01")));
+    }
+
+    #[test]
+    fn older_and_newer_transactions_are_outside() {
+        let range = range(&["--since", "2021-01-01", "--max-block", "20"]).unwrap();
+        let txn = at(10, "2020-12-31");
+        assert!(range.is_before(txn.height, txn.time));
+        assert!(!range.is_after(txn.height, txn.time));
+        let txn = at(21, "2021-06-01");
+        assert!(!range.is_before(txn.height, txn.time));
+        assert!(range.is_after(txn.height, txn.time));
+    }
+
+    #[test]
+    fn out_of_range_tax_year_is_an_error() {
+        assert!(range(&["--tax-year", "300000"]).is_err());
+    }
+
+    #[test]
+    fn empty_range_contains_everything() {
+        assert!(Range::default().contains(&at(1, "2021-01-01")));
+    }
+}
@@ -0,0 +1,104 @@
+use super::Difference;
+use helium_api::{oracle, Client};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// Decimal places USD amounts are reported with, enough for a single DC
+pub const USD_DECIMALS: u32 = 5;
+
+/// Formats a USD amount for the report, leaving the cell empty when it is
+/// unknown for lack of a price
+pub fn usd(amount: Option<Decimal>) -> String {
+    amount
+        .map(|amount| amount.round_dp(USD_DECIMALS).to_string())
+        .unwrap_or_default()
+}
+
+/// Fixed price of one DC in USD
+fn dc_price() -> Decimal {
+    Decimal::new(1, 5)
+}
+
+/// Oracle HNT prices in USD by block height, each looked up only once
+#[derive(Default)]
+pub struct PriceCache {
+    prices: HashMap<u64, Option<Decimal>>,
+}
+
+impl PriceCache {
+    /// The price at a block, if there is one. Blocks from before the price
+    /// oracles started in mid 2020 have none, and neither do blocks whose
+    /// lookup failed; their USD values are left out of the report rather
+    /// than failing it.
+    pub async fn at_block(&mut self, client: &Client, height: u64) -> Option<Decimal> {
+        if let Some(price) = self.prices.get(&height) {
+            return *price;
+        }
+        let price = match oracle::prices::at_block(client, height).await {
+            Ok(price) => Some(price.price.get_decimal()).filter(|price| !price.is_zero()),
+            Err(e) => {
+                eprintln!("warning: no HNT price at block {}: {}", height, e);
+                None
+            }
+        };
+        self.prices.insert(height, price);
+        price
+    }
+}
+
+/// USD value of an entry at the oracle price of its block
+pub struct Valuation {
+    pub hnt: Option<Decimal>,
+    pub fee: Decimal,
+    /// Cost basis of the HNT held after the entry, when tax lots are tracked
+    pub cost_basis: Option<Decimal>,
+}
+
+impl Valuation {
+    pub fn new(
+        difference: &Difference,
+        price: Option<Decimal>,
+        cost_basis: Option<Decimal>,
+    ) -> Valuation {
+        let hnt = difference.hnt.get_decimal();
+        Valuation {
+            hnt: price.map(|price| hnt * price),
+            fee: Decimal::from(difference.fee) * dc_price(),
+            cost_basis,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use helium_api::{Dc, Hnt};
+    use std::str::FromStr;
+
+    fn difference(hnt: &str, fee: u64) -> Difference {
+        Difference {
+            counterparty: None,
+            hnt: Hnt::new(Decimal::from_str(hnt).unwrap()),
+            dc: Dc::from(0),
+            fee,
+        }
+    }
+
+    #[test]
+    fn hnt_is_valued_at_the_price() {
+        let price = Decimal::from_str("1.25").unwrap();
+        let valuation = Valuation::new(&difference("-4", 35_000), Some(price), None);
+        assert_eq!(valuation.hnt, Some(Decimal::from(-5)));
+        assert_eq!(valuation.fee, Decimal::from_str("0.35").unwrap());
+
+        let valuation = Valuation::new(&difference("4", 0), None, None);
+        assert_eq!(valuation.hnt, None);
+        assert_eq!(usd(valuation.hnt), "");
+    }
+
+    #[test]
+    fn usd_is_rounded_to_a_dc() {
+        let amount = Decimal::from_str("1.234567").unwrap();
+        assert_eq!(usd(Some(amount)), "1.23457");
+    }
+}